@@ -1,4 +1,8 @@
-
+#[cfg(feature = "scan")]
+pub mod scan;
 
 #[cfg(feature = "tauri_wasm")]
-pub mod tauri_wasm;
\ No newline at end of file
+pub mod tauri_wasm;
+
+#[cfg(all(test, feature = "tauri_wasm"))]
+mod test;
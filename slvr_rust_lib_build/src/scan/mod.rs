@@ -0,0 +1,682 @@
+mod workspace;
+
+use proc_macro2::LineColumn;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+pub use workspace::{Workspace, WorkspaceError, WorkspaceMember};
+
+/// Reads the crate in `source_directory` and collects every struct, enum, impl block and function
+/// with an attribute other than a doc comment, the base of the code generated by build scripts:
+/// ```ignore
+/// let scan = slvr_rust_lib_build::scan::scan("src");
+/// scan.emit_cargo_directives();
+///
+/// for item in scan.items_with_attribute("tauri_event") {
+///     println!("{}::{} at {}:{}", item.module_path, item.ident, item.file.display(), item.span.line);
+/// }
+/// ```
+/// The crate is read from `lib.rs`, or `main.rs` when there is no `lib.rs`, through its `mod`
+/// declarations, inline modules included. Modules and items whose `#[cfg(...)]` does not hold for the features being built are
+/// skipped. A directory without `lib.rs` or `main.rs` is read as a set of modules, every `.rs`
+/// file directly in it being a module of the crate root.
+///
+/// Called from a build script, the items of every file are cached in `OUT_DIR` by the hash of the
+/// file, so incremental builds only parse the files that changed.
+///
+/// The items are tagged with the name of the package whose `Cargo.toml` is next to `source_directory`,
+/// `Workspace::scan` reads other crates of the workspace.
+pub fn scan(source_directory: impl AsRef<Path>) -> Scan {
+    let source_directory = source_directory.as_ref();
+    let crate_name = source_directory
+        .parent()
+        .and_then(|manifest_dir| workspace::package_name(manifest_dir).ok().flatten())
+        .unwrap_or_default();
+
+    let cfg = ActiveCfg::from_env();
+    let mut scanner = Scanner::new(&cfg, ScanCache::from_env());
+    scanner.scan_directory(source_directory, &crate_name);
+
+    scanner.scan
+}
+
+/// Everything `scan` found in a crate.
+#[derive(Default)]
+pub struct Scan {
+    items: Vec<ScannedItem>,
+    files: Vec<PathBuf>,
+    warnings: Vec<String>,
+}
+
+impl Scan {
+    /// Every item found, in the order they are declared, modules depth first.
+    pub fn items(&self) -> &[ScannedItem] {
+        &self.items
+    }
+
+    /// The items with the attribute `name`, matched by the last segment of the attribute path so
+    /// `#[tauri_command]` and `#[slvr_rust_lib::tauri_command]` both match `tauri_command`.
+    pub fn items_with_attribute<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a ScannedItem> + 'a {
+        self.items
+            .iter()
+            .filter(move |item| item.has_attribute(name))
+    }
+
+    /// Every file that was read, in the order they were found.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// The files that could not be read or parsed and the modules without a file,
+    /// each starting with the `file:line:column` it was found at.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    #[cfg(feature = "tauri_wasm")]
+    pub(crate) fn push_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Prints the warnings as `cargo:warning` and every file that was read as `cargo:rerun-if-changed`.
+    pub fn emit_cargo_directives(&self) {
+        for warning in &self.warnings {
+            println!("cargo:warning={warning}");
+        }
+        for file in &self.files {
+            println!("cargo:rerun-if-changed={}", file.display());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Struct,
+    Enum,
+    Impl,
+    Fn,
+}
+
+/// An item found by `scan`.
+#[derive(Clone)]
+pub struct ScannedItem {
+    /// the name of the package the item is in, as written in its `Cargo.toml`
+    pub crate_name: String,
+    pub kind: ItemKind,
+    /// the name of the item, the last segment of the self type for impl blocks
+    pub ident: syn::Ident,
+    /// the module the item is declared in, ie: `crate::commands::files` or `crate::commands::files::inline`
+    pub module_path: String,
+    pub file: PathBuf,
+    /// where `ident` is in `file`, lines start at 1 and columns at 0
+    pub span: LineColumn,
+    pub attrs: Vec<syn::Attribute>,
+    pub item: syn::Item,
+}
+
+impl ScannedItem {
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attrs
+            .iter()
+            .any(|attribute| attribute_is(attribute, name))
+    }
+
+    /// The path other crates use for the item, ie: `app_messages::files::ReadFile`.
+    pub fn path(&self) -> String {
+        let module_path = match self.module_path.strip_prefix("crate") {
+            Some(module_path) if !self.crate_name.is_empty() => {
+                format!("{}{module_path}", self.crate_name.replace('-', "_"))
+            }
+            _ => self.module_path.clone(),
+        };
+        format!("{module_path}::{}", self.ident)
+    }
+
+    /// `file:line:column` of the item, the way rustc reports locations
+    pub fn location(&self) -> String {
+        location(&self.file, self.span)
+    }
+}
+
+/// `file:line:column`, the way rustc reports locations
+pub(crate) fn location(file: &Path, span: LineColumn) -> String {
+    format!("{}:{}:{}", file.display(), span.line, span.column + 1)
+}
+
+/// checks the last segment of the attribute path so `#[slvr_rust_lib::tauri_command]` also matches
+pub(crate) fn attribute_is(attribute: &syn::Attribute, name: &str) -> bool {
+    attribute
+        .path()
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == name)
+}
+
+/// works out the module path of a file from its location in the source directory,
+/// ie: `src/commands/mod.rs` -> `crate::commands` and `src/commands/files.rs` -> `crate::commands::files`
+pub(crate) fn module_path(source_directory: &Path, file: &Path) -> String {
+    let relative = file
+        .strip_prefix(source_directory)
+        .unwrap_or(file)
+        .with_extension("");
+    let segments: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+
+    let mut module_path = String::from("crate");
+    for (index, segment) in segments.iter().enumerate() {
+        let is_last = index == segments.len() - 1;
+        // `mod.rs` belongs to its directory and `main.rs`/`lib.rs` are the crate root
+        if is_last
+            && (segment == "mod"
+                || (segments.len() == 1 && (segment == "main" || segment == "lib")))
+        {
+            continue;
+        }
+        module_path.push_str("::");
+        module_path.push_str(segment);
+    }
+
+    module_path
+}
+
+/// The `cfg` options of the crate being built, read from the variables cargo sets for build scripts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ActiveCfg {
+    /// the enabled features, upper case with `-` replaced by `_` like cargo passes them
+    features: HashSet<String>,
+    /// the other options, ie: `target_os` -> `["linux"]` or `unix` -> `[]`
+    options: HashMap<String, Vec<String>>,
+}
+
+impl ActiveCfg {
+    pub(crate) fn from_env() -> Self {
+        let mut cfg = ActiveCfg::default();
+
+        for (name, value) in std::env::vars() {
+            if let Some(feature) = name.strip_prefix("CARGO_FEATURE_") {
+                cfg.features.insert(feature.to_string());
+            } else if let Some(option) = name.strip_prefix("CARGO_CFG_") {
+                cfg.options.insert(
+                    option.to_lowercase(),
+                    value
+                        .split(',')
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                );
+            }
+        }
+
+        cfg
+    }
+
+    #[cfg(all(test, feature = "tauri_wasm"))]
+    pub(crate) fn with_features(features: &[&str]) -> Self {
+        ActiveCfg {
+            features: features
+                .iter()
+                .map(|feature| feature_variable(feature))
+                .collect(),
+            options: HashMap::new(),
+        }
+    }
+
+    /// whether every `#[cfg(...)]` of an item holds, predicates that cannot be read count as true
+    pub(crate) fn is_active(&self, attributes: &[syn::Attribute]) -> bool {
+        attributes
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("cfg"))
+            .all(|attribute| {
+                attribute
+                    .parse_args::<syn::Meta>()
+                    .map_or(true, |predicate| self.holds(&predicate))
+            })
+    }
+
+    fn holds(&self, predicate: &syn::Meta) -> bool {
+        match predicate {
+            syn::Meta::Path(path) => path
+                .get_ident()
+                .is_some_and(|name| self.options.contains_key(&name.to_string())),
+            syn::Meta::NameValue(name_value) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) = &name_value.value
+                else {
+                    return true;
+                };
+                if name_value.path.is_ident("feature") {
+                    self.features.contains(&feature_variable(&value.value()))
+                } else {
+                    name_value.path.get_ident().is_some_and(|name| {
+                        self.options
+                            .get(&name.to_string())
+                            .is_some_and(|values| values.contains(&value.value()))
+                    })
+                }
+            }
+            syn::Meta::List(list) => {
+                let Ok(predicates) =
+                    list.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+                else {
+                    return true;
+                };
+                if list.path.is_ident("all") {
+                    predicates.iter().all(|predicate| self.holds(predicate))
+                } else if list.path.is_ident("any") {
+                    predicates.iter().any(|predicate| self.holds(predicate))
+                } else if list.path.is_ident("not") {
+                    !predicates.iter().all(|predicate| self.holds(predicate))
+                } else {
+                    true
+                }
+            }
+        }
+    }
+}
+
+/// the name cargo gives a feature in `CARGO_FEATURE_*`
+fn feature_variable(feature: &str) -> String {
+    feature.to_uppercase().replace('-', "_")
+}
+
+/// The items of a file that can matter to a scan, the rest of the file is not cached.
+struct Snippet {
+    /// the line of the file the snippet starts at, minus one
+    line_offset: usize,
+    /// the item, starting at the beginning of its first line so columns stay the same
+    source: String,
+}
+
+/// Keeps the snippets of every file by the hash of its contents, one cache file per source file.
+pub(crate) struct ScanCache {
+    directory: PathBuf,
+}
+
+impl ScanCache {
+    pub(crate) fn new(directory: PathBuf) -> Self {
+        ScanCache { directory }
+    }
+
+    /// the cache of the build script being run, none outside of build scripts
+    pub(crate) fn from_env() -> Option<Self> {
+        std::env::var_os("OUT_DIR")
+            .map(|out_dir| ScanCache::new(PathBuf::from(out_dir).join("slvr_scan")))
+    }
+
+    fn cache_file(&self, file: &Path) -> PathBuf {
+        self.directory.join(format!(
+            "{:016x}.txt",
+            fnv1a(file.to_string_lossy().as_bytes())
+        ))
+    }
+
+    fn load(&self, file: &Path, hash: u64) -> Option<Vec<Snippet>> {
+        let cached = std::fs::read_to_string(self.cache_file(file)).ok()?;
+        let (cached_hash, mut rest) = cached.split_once('\n')?;
+        if cached_hash != format!("{hash:016x}") {
+            return None;
+        }
+
+        let mut snippets = Vec::new();
+        while !rest.is_empty() {
+            let (header, tail) = rest.split_once('\n')?;
+            let (line_offset, length) = header.split_once(' ')?;
+            let length: usize = length.parse().ok()?;
+            snippets.push(Snippet {
+                line_offset: line_offset.parse().ok()?,
+                source: tail.get(..length)?.to_string(),
+            });
+            rest = tail.get(length..)?.strip_prefix('\n')?;
+        }
+        Some(snippets)
+    }
+
+    /// a cache that cannot be written only costs the next build some time
+    fn store(&self, file: &Path, hash: u64, snippets: &[Snippet]) {
+        let mut cached = format!("{hash:016x}\n");
+        for snippet in snippets {
+            let _ = writeln!(
+                cached,
+                "{} {}\n{}",
+                snippet.line_offset,
+                snippet.source.len(),
+                snippet.source
+            );
+        }
+
+        let _ = std::fs::create_dir_all(&self.directory);
+        let _ = std::fs::write(self.cache_file(file), cached);
+    }
+}
+
+/// Reads a crate from its root files the way rustc does, following `mod` declarations.
+///
+/// Problems are collected as warnings with the file and line they were found at instead of
+/// failing the build.
+pub(crate) struct Scanner<'a> {
+    cfg: &'a ActiveCfg,
+    cache: Option<ScanCache>,
+    /// the crate the items being scanned are tagged with
+    crate_name: String,
+    pub(crate) scan: Scan,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(cfg: &'a ActiveCfg, cache: Option<ScanCache>) -> Self {
+        Scanner {
+            cfg,
+            cache,
+            crate_name: String::new(),
+            scan: Scan::default(),
+        }
+    }
+
+    pub(crate) fn scan_directory(&mut self, source_directory: &Path, crate_name: &str) {
+        // the binary next to a library is another crate, its modules are not the library's
+        let root = ["lib.rs", "main.rs"]
+            .iter()
+            .map(|root| source_directory.join(root))
+            .find(|root| root.is_file());
+        if let Some(root) = root {
+            self.scan_root(&root, crate_name);
+            return;
+        }
+
+        self.crate_name = crate_name.to_string();
+
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(source_directory) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rs"))
+                .collect(),
+            Err(err) => {
+                self.scan.warnings.push(format!(
+                    "{}: could not read the source directory: {err}",
+                    source_directory.display()
+                ));
+                return;
+            }
+        };
+        files.sort();
+
+        for file in files {
+            let module_path = module_path(source_directory, &file);
+            let module_directory = file.with_extension("");
+            self.scan_file(&file, &module_path, &module_directory);
+        }
+    }
+
+    /// reads the crate whose root module is `root`, ie: the `[lib] path` of its manifest
+    pub(crate) fn scan_root(&mut self, root: &Path, crate_name: &str) {
+        self.crate_name = crate_name.to_string();
+        let module_directory = root.parent().unwrap_or(Path::new(""));
+        self.scan_file(root, "crate", module_directory);
+    }
+
+    /// reads the module `module_path` from `file`, its `mod` declarations are looked up in `module_directory`
+    fn scan_file(&mut self, file: &Path, module_path: &str, module_directory: &Path) {
+        // a file declared as a module twice, ie: through `#[path]`, is read once
+        if self.scan.files.iter().any(|scanned| scanned == file) {
+            return;
+        }
+        self.scan.files.push(file.to_path_buf());
+
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                self.scan
+                    .warnings
+                    .push(format!("{}: could not read file: {err}", file.display()));
+                return;
+            }
+        };
+
+        let hash = fnv1a(source.as_bytes());
+        if let Some(snippets) = self.cache.as_ref().and_then(|cache| cache.load(file, hash)) {
+            for snippet in snippets {
+                match syn::parse_file(&snippet.source) {
+                    Ok(ast) => self.scan_items(
+                        ast.items,
+                        module_path,
+                        file,
+                        module_directory,
+                        snippet.line_offset,
+                    ),
+                    // only a cache from another version of syn could get here, so it is read again
+                    Err(_) => {
+                        self.scan_source(&source, hash, file, module_path, module_directory);
+                        return;
+                    }
+                }
+            }
+            return;
+        }
+
+        self.scan_source(&source, hash, file, module_path, module_directory);
+    }
+
+    fn scan_source(
+        &mut self,
+        source: &str,
+        hash: u64,
+        file: &Path,
+        module_path: &str,
+        module_directory: &Path,
+    ) {
+        let ast = match syn::parse_file(source) {
+            Ok(ast) => ast,
+            Err(err) => {
+                self.warn(file, err.span(), 0, &format!("could not parse file: {err}"));
+                return;
+            }
+        };
+
+        // the spans of files syn strips a byte order mark or shebang from do not start at the file
+        if let Some(cache) = &self.cache
+            && !source.starts_with('\u{feff}')
+            && ast.shebang.is_none()
+        {
+            cache.store(file, hash, &snippets(source, &ast));
+        }
+
+        self.scan_items(ast.items, module_path, file, module_directory, 0);
+    }
+
+    /// collects the items of a module, `file` is the file the items are written in
+    pub(crate) fn scan_items(
+        &mut self,
+        items: Vec<syn::Item>,
+        module_path: &str,
+        file: &Path,
+        module_directory: &Path,
+        line_offset: usize,
+    ) {
+        for item in items {
+            let (kind, attrs, ident) = match &item {
+                syn::Item::Struct(item_struct) => (
+                    ItemKind::Struct,
+                    &item_struct.attrs,
+                    item_struct.ident.clone(),
+                ),
+                syn::Item::Enum(item_enum) => {
+                    (ItemKind::Enum, &item_enum.attrs, item_enum.ident.clone())
+                }
+                syn::Item::Fn(item_fn) => (ItemKind::Fn, &item_fn.attrs, item_fn.sig.ident.clone()),
+                syn::Item::Impl(item_impl) => {
+                    let syn::Type::Path(self_path) = &*item_impl.self_ty else {
+                        continue;
+                    };
+                    let Some(segment) = self_path.path.segments.last() else {
+                        continue;
+                    };
+                    (ItemKind::Impl, &item_impl.attrs, segment.ident.clone())
+                }
+                syn::Item::Mod(item_mod) => {
+                    if self.cfg.is_active(&item_mod.attrs) {
+                        self.scan_module(
+                            item_mod.clone(),
+                            module_path,
+                            file,
+                            module_directory,
+                            line_offset,
+                        );
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if !has_attributes(attrs) || !self.cfg.is_active(attrs) {
+                continue;
+            }
+
+            let mut span = ident.span().start();
+            span.line += line_offset;
+            self.scan.items.push(ScannedItem {
+                crate_name: self.crate_name.clone(),
+                kind,
+                ident,
+                module_path: module_path.to_string(),
+                file: file.to_path_buf(),
+                span,
+                attrs: attrs.clone(),
+                item,
+            });
+        }
+    }
+
+    /// follows an inline module or a `mod` declaration to its file
+    fn scan_module(
+        &mut self,
+        item_mod: syn::ItemMod,
+        parent_path: &str,
+        file: &Path,
+        parent_directory: &Path,
+        line_offset: usize,
+    ) {
+        let module_path = format!("{parent_path}::{}", item_mod.ident);
+        let name = item_mod.ident.unraw().to_string();
+
+        if let Some((_, items)) = item_mod.content {
+            self.scan_items(
+                items,
+                &module_path,
+                file,
+                &parent_directory.join(&name),
+                line_offset,
+            );
+            return;
+        }
+
+        // `#[path]` is relative to the directory of the declaring file, the module is read like a `mod.rs`
+        let path_attribute = item_mod.attrs.iter().find_map(|attribute| {
+            let syn::Meta::NameValue(name_value) = &attribute.meta else {
+                return None;
+            };
+            match &name_value.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(path),
+                    ..
+                }) if name_value.path.is_ident("path") => Some(path.value()),
+                _ => None,
+            }
+        });
+        let candidates = match path_attribute {
+            Some(path) => {
+                let module_file = file.parent().unwrap_or(Path::new("")).join(path);
+                let module_directory = module_file.parent().unwrap_or(Path::new("")).to_path_buf();
+                vec![(module_file, module_directory)]
+            }
+            None => {
+                let module_directory = parent_directory.join(&name);
+                vec![
+                    (
+                        parent_directory.join(format!("{name}.rs")),
+                        module_directory.clone(),
+                    ),
+                    (module_directory.join("mod.rs"), module_directory),
+                ]
+            }
+        };
+
+        match candidates
+            .into_iter()
+            .find(|(module_file, _)| module_file.is_file())
+        {
+            Some((module_file, module_directory)) => {
+                self.scan_file(&module_file, &module_path, &module_directory)
+            }
+            None => self.warn(
+                file,
+                item_mod.ident.span(),
+                line_offset,
+                &format!("could not find the file of module `{name}`"),
+            ),
+        }
+    }
+
+    fn warn(&mut self, file: &Path, span: proc_macro2::Span, line_offset: usize, message: &str) {
+        let start = span.start();
+        self.scan.warnings.push(format!(
+            "{}:{}:{}: {message}",
+            file.display(),
+            start.line + line_offset,
+            start.column + 1
+        ));
+    }
+}
+
+/// whether an item has attributes other than doc comments, the items a scan collects
+fn has_attributes(attrs: &[syn::Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attribute| !attribute.path().is_ident("doc"))
+}
+
+/// the items of a file a scan can use: modules and the items it collects
+fn snippets(source: &str, ast: &syn::File) -> Vec<Snippet> {
+    ast.items
+        .iter()
+        .filter(|item| match item {
+            syn::Item::Mod(_) => true,
+            syn::Item::Struct(item) => has_attributes(&item.attrs),
+            syn::Item::Enum(item) => has_attributes(&item.attrs),
+            syn::Item::Impl(item) => has_attributes(&item.attrs),
+            syn::Item::Fn(item) => has_attributes(&item.attrs),
+            _ => false,
+        })
+        .map(|item| {
+            let span = item.span();
+            let range = span.byte_range();
+            let line_start = source[..range.start]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            Snippet {
+                line_offset: span.start().line - 1,
+                source: source[line_start..range.end].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// FNV-1a, the hashes end up in file names and caches that outlive the build script
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
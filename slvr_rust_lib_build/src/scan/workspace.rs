@@ -0,0 +1,280 @@
+use crate::scan::{ActiveCfg, Scan, ScanCache, Scanner};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// A cargo workspace, read from the `Cargo.toml` files on disk without calling cargo.
+///
+/// Lets the build scripts of several crates generate code from a crate they share:
+/// ```ignore
+/// let workspace = Workspace::discover()?;
+/// let scan = workspace.scan(&["app_messages", env!("CARGO_PKG_NAME")]);
+/// scan.emit_cargo_directives();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    members: Vec<WorkspaceMember>,
+}
+
+/// A crate of a `Workspace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMember {
+    /// the package name, as written in its `Cargo.toml`
+    pub name: String,
+    pub manifest_dir: PathBuf,
+    /// the file the crate is read from: the library root, `[lib] path` or `src/lib.rs`, or the
+    /// first binary root, `[[bin]] path` or `src/main.rs`, for a package without a library
+    pub root_file: PathBuf,
+    /// the directory of `root_file`, where its `mod` declarations are looked up
+    pub source_directory: PathBuf,
+}
+
+/// A `Cargo.toml` that could not be read.
+#[derive(Debug)]
+pub struct WorkspaceError {
+    pub manifest: PathBuf,
+    pub message: String,
+}
+
+impl Display for WorkspaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.manifest.display(), self.message)
+    }
+}
+
+impl Error for WorkspaceError {}
+
+impl Workspace {
+    /// The workspace of the crate whose build script is running, found from `CARGO_MANIFEST_DIR`.
+    pub fn discover() -> Result<Workspace, WorkspaceError> {
+        let manifest_dir =
+            std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| WorkspaceError {
+                manifest: PathBuf::from("Cargo.toml"),
+                message: String::from(
+                    "CARGO_MANIFEST_DIR is not set, is this being called from a build script?",
+                ),
+            })?;
+        Workspace::from_manifest_dir(manifest_dir)
+    }
+
+    /// The workspace of the crate in `manifest_dir`, the closest directory up from it with a
+    /// `Cargo.toml` holding a `[workspace]` table. A crate outside of any workspace is its only member.
+    pub fn from_manifest_dir(manifest_dir: impl AsRef<Path>) -> Result<Workspace, WorkspaceError> {
+        let manifest_dir = manifest_dir.as_ref();
+
+        for directory in manifest_dir.ancestors() {
+            let manifest = directory.join("Cargo.toml");
+            if !manifest.is_file() {
+                continue;
+            }
+            let table = read_manifest(&manifest)?;
+            if let Some(workspace) = table.get("workspace") {
+                return Workspace::from_table(directory, &table, workspace);
+            }
+        }
+
+        let member = read_member(manifest_dir)?;
+        Ok(Workspace {
+            root: manifest_dir.to_path_buf(),
+            members: member.into_iter().collect(),
+        })
+    }
+
+    fn from_table(
+        root: &Path,
+        table: &toml::Table,
+        workspace: &toml::Value,
+    ) -> Result<Workspace, WorkspaceError> {
+        let patterns = |key: &str| -> Vec<String> {
+            workspace
+                .get(key)
+                .and_then(toml::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_string)
+                .collect()
+        };
+        let excluded: Vec<PathBuf> = patterns("exclude")
+            .iter()
+            .flat_map(|pattern| expand_pattern(root, pattern))
+            .collect();
+
+        let mut directories = Vec::new();
+        // the root is a member when it is a package itself
+        if table.contains_key("package") {
+            directories.push(root.to_path_buf());
+        }
+        for pattern in patterns("members") {
+            for directory in expand_pattern(root, &pattern) {
+                if directory.join("Cargo.toml").is_file()
+                    && !excluded.contains(&directory)
+                    && !directories.contains(&directory)
+                {
+                    directories.push(directory);
+                }
+            }
+        }
+
+        let mut members = Vec::new();
+        for directory in directories {
+            members.extend(read_member(&directory)?);
+        }
+
+        Ok(Workspace {
+            root: root.to_path_buf(),
+            members,
+        })
+    }
+
+    /// The directory of the workspace `Cargo.toml`.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn members(&self) -> &[WorkspaceMember] {
+        &self.members
+    }
+
+    pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+        self.members.iter().find(|member| member.name == name)
+    }
+
+    /// Scans the members named in `crates` like `scan::scan`, in that order, and tags every item
+    /// with the crate it was found in. Names that are not members are reported as warnings.
+    ///
+    /// `#[cfg(feature = ...)]` is checked against the features of the crate being built,
+    /// the features other crates are built with are not known to its build script.
+    pub fn scan(&self, crates: &[&str]) -> Scan {
+        let cfg = ActiveCfg::from_env();
+        let mut scanner = Scanner::new(&cfg, ScanCache::from_env());
+
+        for crate_name in crates {
+            match self.member(crate_name) {
+                Some(member) => {
+                    scanner
+                        .scan
+                        .files
+                        .push(member.manifest_dir.join("Cargo.toml"));
+                    scanner.scan_root(&member.root_file, &member.name);
+                }
+                None => scanner.scan.warnings.push(format!(
+                    "{}: `{crate_name}` is not a member of the workspace",
+                    self.root.join("Cargo.toml").display()
+                )),
+            }
+        }
+
+        scanner.scan
+    }
+}
+
+/// the name of the package in `manifest_dir`, `None` for a virtual manifest
+pub(crate) fn package_name(manifest_dir: &Path) -> Result<Option<String>, WorkspaceError> {
+    Ok(read_member(manifest_dir)?.map(|member| member.name))
+}
+
+fn read_member(manifest_dir: &Path) -> Result<Option<WorkspaceMember>, WorkspaceError> {
+    let manifest = manifest_dir.join("Cargo.toml");
+    let table = read_manifest(&manifest)?;
+
+    let Some(package) = table.get("package") else {
+        return Ok(None);
+    };
+    let name = package
+        .get("name")
+        .and_then(toml::Value::as_str)
+        .ok_or_else(|| WorkspaceError {
+            manifest: manifest.clone(),
+            message: String::from("the package has no name"),
+        })?;
+    let root_file = root_file(manifest_dir, &table);
+    let source_directory = root_file
+        .parent()
+        .map_or_else(|| manifest_dir.join("src"), Path::to_path_buf);
+
+    Ok(Some(WorkspaceMember {
+        name: name.to_string(),
+        manifest_dir: manifest_dir.to_path_buf(),
+        root_file,
+        source_directory,
+    }))
+}
+
+/// the library root of the package, or its first binary root when it has no library, a package
+/// with both is read from the library, the binary is another crate with its own modules
+fn root_file(manifest_dir: &Path, table: &toml::Table) -> PathBuf {
+    let path = |target: Option<&toml::Value>| {
+        target
+            .and_then(|target| target.get("path"))
+            .and_then(toml::Value::as_str)
+            .map(|path| manifest_dir.join(path))
+    };
+
+    let library = path(table.get("lib")).unwrap_or_else(|| manifest_dir.join("src/lib.rs"));
+    if table.contains_key("lib") || library.is_file() {
+        return library;
+    }
+    let first_binary = table
+        .get("bin")
+        .and_then(toml::Value::as_array)
+        .and_then(|binaries| binaries.first());
+    path(first_binary).unwrap_or_else(|| manifest_dir.join("src/main.rs"))
+}
+
+fn read_manifest(manifest: &Path) -> Result<toml::Table, WorkspaceError> {
+    let error = |message: String| WorkspaceError {
+        manifest: manifest.to_path_buf(),
+        message,
+    };
+
+    std::fs::read_to_string(manifest)
+        .map_err(|err| error(format!("could not read file: {err}")))?
+        .parse()
+        .map_err(|err: toml::de::Error| error(format!("could not parse file: {err}")))
+}
+
+/// the directories a `members` or `exclude` entry names, `*` and `?` match within a path component
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut directories = vec![root.to_path_buf()];
+
+    for component in pattern.split('/').filter(|component| !component.is_empty()) {
+        if !component.contains(['*', '?']) {
+            for directory in &mut directories {
+                directory.push(component);
+            }
+            continue;
+        }
+
+        directories = directories
+            .iter()
+            .filter_map(|directory| std::fs::read_dir(directory).ok())
+            .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_dir()
+                    && path.file_name().is_some_and(|name| {
+                        wildcard_matches(component.as_bytes(), name.as_encoded_bytes())
+                    })
+            })
+            .collect();
+        directories.sort();
+    }
+
+    directories
+}
+
+fn wildcard_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_matches(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_matches(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_matches(&pattern[1..], &name[1..]),
+        (Some(expected), Some(found)) if expected == found => {
+            wildcard_matches(&pattern[1..], &name[1..])
+        }
+        _ => false,
+    }
+}
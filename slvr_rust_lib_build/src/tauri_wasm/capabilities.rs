@@ -0,0 +1,273 @@
+use crate::scan::attribute_is;
+use crate::tauri_wasm::{
+    BUILTIN_COMMANDS, COMMAND_ATTRIBUTE, CommandImpl, MessageStruct, MessageStructs,
+    command_message, is_local_module,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+const PERMISSION_HEADER: &str =
+    "# generated by slvr_rust_lib_build from the `permission` of the tauri commands, do not edit\n";
+const CAPABILITY_DESCRIPTION: &str =
+    "generated by slvr_rust_lib_build from the `windows` of the tauri commands, do not edit";
+/// allows the commands `tauri_commands!()` handles itself, part of every generated capability
+const BUILTIN_PERMISSION: &str = "slvr-builtin";
+
+/// Writes the tauri permissions and capabilities of the commands into `app_directory`,
+/// the directory of `tauri.conf.json`:
+/// ```ignore
+/// #[tauri_command(permission = "files", windows = ["main", "settings"])]
+/// pub struct ReadFile { pub path: PathBuf }
+/// ```
+/// `permissions/<permission>.toml` allows every command with that `permission`, commands without
+/// one get their own `allow-<command-name>` permission. `capabilities/commands-<window>.json` grants
+/// a window the permissions of every command listing it in `windows`, and the `slvr-builtin`
+/// permission for the batch, cancellation and handshake commands. Tauri only checks a batch as
+/// `__slvr_batch`, so the batch command checks every command in it against its `windows` itself.
+///
+/// Commands no window may send are reported as cargo warnings, window labels that give the same
+/// capability identifier, ie: `main-window` and `main_window`, fail the build. Files generated
+/// before that are not generated anymore are removed, files written by hand are never overwritten.
+/// Commands with a `plugin:` name are allowed by the permissions of their plugin and left out.
+pub fn write_capabilities(message_structs: &MessageStructs, app_directory: impl AsRef<Path>) {
+    let app_directory = app_directory.as_ref();
+    let files = capability_files(message_structs);
+
+    if !files.conflicts.is_empty() {
+        panic!(
+            "Conflicting window labels in the tauri commands:\n{}",
+            files.conflicts.join("\n")
+        );
+    }
+    for warning in &files.warnings {
+        println!("cargo:warning={warning}");
+    }
+
+    write_directory(
+        &app_directory.join("permissions"),
+        "toml",
+        &files.permissions,
+        |content| content.starts_with(PERMISSION_HEADER),
+    );
+    write_directory(
+        &app_directory.join("capabilities"),
+        "json",
+        &files.capabilities,
+        |content| content.contains(CAPABILITY_DESCRIPTION),
+    );
+}
+
+/// the generated files by their name without extension, the commands without a capability and the
+/// windows whose capabilities would overwrite each other
+#[derive(Default)]
+pub(crate) struct CapabilityFiles {
+    pub(crate) permissions: BTreeMap<String, String>,
+    pub(crate) capabilities: BTreeMap<String, String>,
+    pub(crate) warnings: Vec<String>,
+    pub(crate) conflicts: Vec<String>,
+}
+
+pub(crate) fn capability_files(message_structs: &MessageStructs) -> CapabilityFiles {
+    let mut permissions: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    let mut windows: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut files = CapabilityFiles::default();
+
+    for command in &message_structs.commands {
+        if !is_local_module(&command.module_path) || command.command_name.starts_with("plugin:") {
+            continue;
+        }
+
+        let message = command_message(&message_structs.messages, command);
+        let access = message.map(command_access).unwrap_or_default();
+        let permission = access
+            .permission
+            .unwrap_or_else(|| format!("allow-{}", identifier(&command.command_name)));
+        permissions
+            .entry(permission.clone())
+            .or_default()
+            .insert(&command.command_name);
+
+        if access.windows.is_empty() {
+            files.warnings.push(format!(
+                "{}: no window may send the command `{}`, list the windows allowed to with \
+                 `#[tauri_command(windows = [\"main\"])]`",
+                message
+                    .map_or(&command.file, |message| &message.file)
+                    .display(),
+                command.command_name,
+            ));
+        }
+        for window in access.windows {
+            windows
+                .entry(window)
+                .or_default()
+                .insert(permission.clone());
+        }
+    }
+
+    if !windows.is_empty() {
+        permissions.insert(
+            BUILTIN_PERMISSION.to_string(),
+            BUILTIN_COMMANDS.into_iter().collect(),
+        );
+    }
+
+    for (permission, commands) in &permissions {
+        let allowed: Vec<String> = commands.iter().map(|command| quoted(command)).collect();
+        let described: Vec<String> = commands
+            .iter()
+            .map(|command| format!("`{command}`"))
+            .collect();
+        files.permissions.insert(
+            permission.clone(),
+            format!(
+                "{PERMISSION_HEADER}\n[[permission]]\nidentifier = {}\ndescription = {}\ncommands.allow = [{}]\n",
+                quoted(permission),
+                quoted(&format!("Allows {}.", described.join(", "))),
+                allowed.join(", "),
+            ),
+        );
+    }
+
+    let mut identifiers: BTreeMap<String, &str> = BTreeMap::new();
+    for (window, window_permissions) in &windows {
+        let identifier = format!("commands-{}", identifier(window));
+        if let Some(other) = identifiers.insert(identifier.clone(), window) {
+            files.conflicts.push(format!(
+                "the windows `{other}` and `{window}` would both get the capability `{identifier}`"
+            ));
+            continue;
+        }
+        let granted: Vec<String> = std::iter::once(BUILTIN_PERMISSION)
+            .chain(window_permissions.iter().map(String::as_str))
+            .map(quoted)
+            .collect();
+        files.capabilities.insert(
+            identifier.clone(),
+            format!(
+                "{{\n  \"identifier\": {},\n  \"description\": {},\n  \"windows\": [{}],\n  \"permissions\": [\n    {}\n  ]\n}}\n",
+                quoted(&identifier),
+                quoted(CAPABILITY_DESCRIPTION),
+                quoted(window),
+                granted.join(",\n    "),
+            ),
+        );
+    }
+
+    files
+}
+
+/// the windows the generated capabilities allow to send `command`, also checked by the batch command
+pub(crate) fn command_windows(
+    message_structs: &MessageStructs,
+    command: &CommandImpl,
+) -> Vec<String> {
+    command_message(&message_structs.messages, command)
+        .map(|message| command_access(message).windows)
+        .unwrap_or_default()
+}
+
+/// what `#[tauri_command(permission = "...", windows = [...])]` says about a command
+#[derive(Default)]
+struct CommandAccess {
+    permission: Option<String>,
+    windows: Vec<String>,
+}
+
+fn command_access(message: &MessageStruct) -> CommandAccess {
+    let mut access = CommandAccess::default();
+
+    for attribute in message.item.attrs() {
+        if attribute_is(attribute, COMMAND_ATTRIBUTE)
+            && matches!(attribute.meta, syn::Meta::List(_))
+        {
+            let _ = attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("permission") {
+                    access.permission = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("windows") {
+                    let windows: syn::ExprArray = meta.value()?.parse()?;
+                    access
+                        .windows
+                        .extend(windows.elems.iter().filter_map(|window| match window {
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: syn::Lit::Str(label),
+                                ..
+                            }) => Some(label.value()),
+                            _ => None,
+                        }));
+                } else if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    access
+}
+
+/// `name` as a tauri identifier, which only takes lowercase ascii letters, digits and `-`
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// a string literal for both toml and json
+fn quoted(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// writes the files that changed, as tauri rebuilds when they are touched,
+/// and removes the generated files that are not generated anymore
+fn write_directory(
+    directory: &Path,
+    extension: &str,
+    files: &BTreeMap<String, String>,
+    is_generated: impl Fn(&str) -> bool,
+) {
+    if let Ok(entries) = std::fs::read_dir(directory) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let stale = path.extension().is_some_and(|found| found == extension)
+                && path
+                    .file_stem()
+                    .and_then(|name| name.to_str())
+                    .is_none_or(|name| !files.contains_key(name));
+            if stale && std::fs::read_to_string(&path).is_ok_and(|content| is_generated(&content)) {
+                std::fs::remove_file(&path).expect("Error while removing a generated tauri file");
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return;
+    }
+    std::fs::create_dir_all(directory).expect("Error while creating a tauri directory");
+    for (name, content) in files {
+        let path = directory.join(format!("{name}.{extension}"));
+        match std::fs::read_to_string(&path) {
+            Ok(existing) if existing == *content => {}
+            Ok(existing) if !is_generated(&existing) => println!(
+                "cargo:warning={}: written by hand, the generated file was not written",
+                path.display()
+            ),
+            _ => std::fs::write(&path, content).expect("Error while writing a tauri file"),
+        }
+    }
+}
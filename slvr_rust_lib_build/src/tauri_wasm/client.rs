@@ -0,0 +1,342 @@
+use crate::scan::ActiveCfg;
+use crate::tauri_wasm::{MessageItem, MessageStructs, is_local_module};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::visit_mut::VisitMut;
+use syn::{ImplItem, Item, ItemUse, UseTree};
+
+pub(crate) const CLIENT_FILE_NAME: &str = "tauri_client.rs";
+
+/// Writes the messages and commands of the backend to `OUT_DIR/tauri_client.rs`, so the frontend
+/// does not have to declare them a second time:
+/// ```ignore
+/// // build.rs of the frontend
+/// let message_structs = resolve_message_structs("../src-tauri/src");
+/// write_frontend_client(&message_structs);
+///
+/// // lib.rs of the frontend, built with `tauri_wasm_frontend`
+/// include!(concat!(env!("OUT_DIR"), "/tauri_client.rs"));
+/// ```
+/// The messages are copied as they are, the `#[tauri_command_impl]` blocks without `handle`, into
+/// the same modules as in the backend, so the file has to be included in the crate root.
+/// `#[cfg(...)]` attributes are evaluated with the cfg and features of the frontend being built,
+/// which the scan in its build script used as well, not with those of the backend. They are dropped
+/// along with the fields, variants and impl items they leave out.
+/// Only the `use` declarations the copied items refer to are copied. Types that are neither
+/// messages nor from the standard library or another crate of the frontend have to be marked
+/// `#[tauri_message]` to be part of the client.
+///
+/// Messages and commands of other crates, found by `resolve_workspace_message_structs`, are left
+/// out, the frontend can depend on those crates itself.
+pub fn write_frontend_client(message_structs: &MessageStructs) {
+    let out_dir = std::env::var("OUT_DIR")
+        .expect("OUT_DIR is not set, is this being called from a build script?");
+
+    std::fs::write(
+        PathBuf::from(out_dir).join(CLIENT_FILE_NAME),
+        frontend_client(message_structs, &ActiveCfg::from_env()),
+    )
+    .expect("Error while writing frontend client");
+}
+
+/// a module of the generated client and the files its items were copied from
+#[derive(Default)]
+struct ClientModule {
+    items: Vec<Item>,
+    files: Vec<PathBuf>,
+    modules: BTreeMap<String, ClientModule>,
+}
+
+pub(crate) fn frontend_client(message_structs: &MessageStructs, cfg: &ActiveCfg) -> String {
+    let mut root = ClientModule::default();
+
+    for message in &message_structs.messages {
+        if !is_local_module(&message.module_path) {
+            continue;
+        }
+        let item = match &message.item {
+            MessageItem::Struct(item) => Item::Struct(item.clone()),
+            MessageItem::Enum(item) => Item::Enum(item.clone()),
+        };
+        root.module(&message.module_path)
+            .push(item, &message.file, cfg);
+    }
+
+    let mut copied = HashSet::new();
+    for command in &message_structs.commands {
+        // the instances of a generic command share their impl block
+        if !is_local_module(&command.module_path)
+            || !copied.insert((
+                command.file.clone(),
+                command.item.to_token_stream().to_string(),
+            ))
+        {
+            continue;
+        }
+        let mut item = command.item.clone();
+        item.items.retain(|impl_item| match impl_item {
+            ImplItem::Fn(function) => function.sig.ident != "handle",
+            _ => true,
+        });
+        root.module(&command.module_path)
+            .push(Item::Impl(item), &command.file, cfg);
+    }
+
+    let generated: HashSet<String> = root.modules.keys().cloned().collect();
+    let mut files = HashMap::new();
+    let tokens = root.to_tokens(true, &generated, &mut files);
+    let file: syn::File = syn::parse2(tokens).expect("generated frontend client is not valid rust");
+
+    format!(
+        "// generated by slvr_rust_lib_build, do not edit\n{}",
+        prettyplease::unparse(&file)
+    )
+}
+
+impl ClientModule {
+    fn module(&mut self, module_path: &str) -> &mut ClientModule {
+        module_path.split("::").skip(1).fold(self, |module, name| {
+            module.modules.entry(name.to_string()).or_default()
+        })
+    }
+
+    fn push(&mut self, mut item: Item, file: &Path, cfg: &ActiveCfg) {
+        ApplyCfg(cfg).visit_item_mut(&mut item);
+        self.items.push(item);
+        if !self.files.iter().any(|known| known == file) {
+            self.files.push(file.to_path_buf());
+        }
+    }
+
+    fn to_tokens(
+        &self,
+        is_root: bool,
+        generated: &HashSet<String>,
+        files: &mut HashMap<PathBuf, Option<syn::File>>,
+    ) -> TokenStream {
+        let mut referenced = HashSet::new();
+        for item in &self.items {
+            collect_idents(item.to_token_stream(), &mut referenced);
+        }
+
+        let mut uses = Vec::new();
+        for file in &self.files {
+            let parsed = files.entry(file.clone()).or_insert_with(|| {
+                std::fs::read_to_string(file)
+                    .ok()
+                    .and_then(|source| syn::parse_file(&source).ok())
+            });
+            let Some(parsed) = parsed else {
+                continue;
+            };
+            for item_use in module_uses(&parsed.items, &self.items) {
+                if let Some(tree) =
+                    used_tree(&item_use.tree, &mut Vec::new(), &referenced, generated)
+                {
+                    let item_use = ItemUse {
+                        tree,
+                        ..item_use.clone()
+                    };
+                    let tokens = item_use.to_token_stream().to_string();
+                    if !uses
+                        .iter()
+                        .any(|known: &ItemUse| known.to_token_stream().to_string() == tokens)
+                    {
+                        uses.push(item_use);
+                    }
+                }
+            }
+        }
+
+        let items = &self.items;
+        let modules = self.modules.iter().map(|(name, module)| {
+            let ident: syn::Ident =
+                syn::parse_str(name).expect("generated module name is not valid rust");
+            let content = module.to_tokens(false, generated, files);
+            quote! {
+                #[allow(dead_code, unused_imports)]
+                pub mod #ident {
+                    #content
+                }
+            }
+        });
+
+        if is_root {
+            // the crate root cannot take inner attributes through `include!`
+            quote! {
+                #(#[allow(unused_imports)] #uses)*
+                #(#[allow(dead_code)] #items)*
+                #(#modules)*
+            }
+        } else {
+            quote! {
+                #(#uses)*
+                #(#items)*
+                #(#modules)*
+            }
+        }
+    }
+}
+
+/// the `use` declarations of the module the copied items come from, the file itself or the
+/// inline module in it holding them
+fn module_uses<'a>(file_items: &'a [Item], copied: &[Item]) -> Vec<&'a ItemUse> {
+    let copied: Vec<String> = copied.iter().filter_map(item_identity).collect();
+
+    fn find<'a>(items: &'a [Item], copied: &[String]) -> Option<&'a [Item]> {
+        if items
+            .iter()
+            .filter_map(item_identity)
+            .any(|identity| copied.contains(&identity))
+        {
+            return Some(items);
+        }
+        items.iter().find_map(|item| match item {
+            Item::Mod(item_mod) => item_mod
+                .content
+                .as_ref()
+                .and_then(|(_, content)| find(content, copied)),
+            _ => None,
+        })
+    }
+
+    find(file_items, &copied)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Use(item_use) => Some(item_use),
+            _ => None,
+        })
+        .collect()
+}
+
+/// what tells a copied item apart from the others, the copies lost their `cfg`s and `handle`
+fn item_identity(item: &Item) -> Option<String> {
+    match item {
+        Item::Struct(item) => Some(item.ident.to_string()),
+        Item::Enum(item) => Some(item.ident.to_string()),
+        Item::Impl(item) => Some(format!(
+            "impl {} for {}",
+            item.trait_
+                .as_ref()
+                .map(|(_, trait_path, _)| trait_path.to_token_stream().to_string())
+                .unwrap_or_default(),
+            item.self_ty.to_token_stream()
+        )),
+        _ => None,
+    }
+}
+
+/// the part of a use tree that imports names the copied items refer to, globs are kept when they
+/// import from the generated modules, the parent modules or `slvr_rust_lib`
+fn used_tree(
+    tree: &UseTree,
+    prefix: &mut Vec<String>,
+    referenced: &HashSet<String>,
+    generated: &HashSet<String>,
+) -> Option<UseTree> {
+    match tree {
+        UseTree::Path(use_path) => {
+            prefix.push(use_path.ident.to_string());
+            let used = used_tree(&use_path.tree, prefix, referenced, generated);
+            prefix.pop();
+            Some(UseTree::Path(syn::UsePath {
+                tree: Box::new(used?),
+                ..use_path.clone()
+            }))
+        }
+        UseTree::Name(use_name) if use_name.ident == "self" => prefix
+            .last()
+            .is_some_and(|module| referenced.contains(module))
+            .then(|| tree.clone()),
+        UseTree::Name(use_name) => referenced
+            .contains(&use_name.ident.to_string())
+            .then(|| tree.clone()),
+        UseTree::Rename(use_rename) => referenced
+            .contains(&use_rename.rename.to_string())
+            .then(|| tree.clone()),
+        UseTree::Glob(_) => {
+            let from_generated = match prefix.first().map(String::as_str) {
+                Some("super" | "self" | "slvr_rust_lib") => true,
+                Some("crate") => prefix
+                    .get(1)
+                    .is_some_and(|module| generated.contains(module)),
+                _ => false,
+            };
+            from_generated.then(|| tree.clone())
+        }
+        UseTree::Group(use_group) => {
+            let items: syn::punctuated::Punctuated<UseTree, syn::Token![,]> = use_group
+                .items
+                .iter()
+                .filter_map(|item| used_tree(item, prefix, referenced, generated))
+                .collect();
+            (!items.is_empty()).then(|| {
+                UseTree::Group(syn::UseGroup {
+                    items,
+                    ..use_group.clone()
+                })
+            })
+        }
+    }
+}
+
+/// every identifier in `tokens`, attributes included, which finds more than is referenced but never
+/// misses a name
+fn collect_idents(tokens: TokenStream, idents: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+/// leaves out the fields, variants and impl items whose `#[cfg(...)]` does not hold and drops the
+/// `#[cfg(...)]` attributes, the scan already left out the items that are not built
+struct ApplyCfg<'a>(&'a ActiveCfg);
+
+impl VisitMut for ApplyCfg<'_> {
+    fn visit_attributes_mut(&mut self, attributes: &mut Vec<syn::Attribute>) {
+        attributes.retain(|attribute| !attribute.path().is_ident("cfg"));
+    }
+
+    fn visit_fields_named_mut(&mut self, fields: &mut syn::FieldsNamed) {
+        fields.named = std::mem::take(&mut fields.named)
+            .into_iter()
+            .filter(|field| self.0.is_active(&field.attrs))
+            .collect();
+        syn::visit_mut::visit_fields_named_mut(self, fields);
+    }
+
+    fn visit_fields_unnamed_mut(&mut self, fields: &mut syn::FieldsUnnamed) {
+        fields.unnamed = std::mem::take(&mut fields.unnamed)
+            .into_iter()
+            .filter(|field| self.0.is_active(&field.attrs))
+            .collect();
+        syn::visit_mut::visit_fields_unnamed_mut(self, fields);
+    }
+
+    fn visit_item_enum_mut(&mut self, item: &mut syn::ItemEnum) {
+        item.variants = std::mem::take(&mut item.variants)
+            .into_iter()
+            .filter(|variant| self.0.is_active(&variant.attrs))
+            .collect();
+        syn::visit_mut::visit_item_enum_mut(self, item);
+    }
+
+    fn visit_item_impl_mut(&mut self, item: &mut syn::ItemImpl) {
+        item.items.retain(|impl_item| match impl_item {
+            ImplItem::Const(impl_item) => self.0.is_active(&impl_item.attrs),
+            ImplItem::Fn(impl_item) => self.0.is_active(&impl_item.attrs),
+            ImplItem::Type(impl_item) => self.0.is_active(&impl_item.attrs),
+            _ => true,
+        });
+        syn::visit_mut::visit_item_impl_mut(self, item);
+    }
+}
@@ -0,0 +1,462 @@
+pub(crate) mod capabilities;
+pub(crate) mod client;
+pub(crate) mod registry;
+pub(crate) mod schema;
+pub(crate) mod typescript;
+pub(crate) mod validate;
+
+use crate::scan::{Scan, ScannedItem, Workspace, WorkspaceError, attribute_is, location};
+use proc_macro2::LineColumn;
+use slvr_rust_lib_macro_support::generics::{
+    Substitutions, instance_substitutions, trait_type_argument,
+};
+use slvr_rust_lib_macro_support::to_snake_case::ToSnakeCase;
+use std::path::{Path, PathBuf};
+use syn::ext::IdentExt;
+use syn::{ImplItem, ItemEnum, ItemImpl, ItemStruct, Type};
+
+pub use capabilities::write_capabilities;
+pub use client::write_frontend_client;
+pub use schema::schema_fingerprint;
+pub use typescript::write_typescript_bindings;
+pub use validate::{ConflictKind, NameConflict, validate_message_structs};
+
+/// the attributes that mark a struct or enum as a message
+const MESSAGE_ATTRIBUTES: [&str; 3] = ["tauri_message", "tauri_command", "tauri_response"];
+const COMMAND_ATTRIBUTE: &str = "tauri_command";
+const COMMAND_IMPL_ATTRIBUTE: &str = "tauri_command_impl";
+const EVENT_ATTRIBUTE: &str = "tauri_event";
+/// the commands `tauri_commands!()` handles itself, copies of the names in `slvr_rust_lib_common`
+/// so build scripts do not build its wasm dependencies, a test checks they match
+pub(crate) const BUILTIN_COMMANDS: [&str; 3] =
+    ["__slvr_batch", "__tauri_wasm_cancel", "__slvr_handshake"];
+
+/// Everything `resolve_message_structs` found in the source tree.
+#[derive(Default)]
+pub struct MessageStructs {
+    pub messages: Vec<MessageStruct>,
+    pub commands: Vec<CommandImpl>,
+    /// the structs and enums marked `#[tauri_event]`
+    pub events: Vec<MessageStruct>,
+}
+
+/// A struct or enum marked with one of the message attributes.
+pub struct MessageStruct {
+    pub item: MessageItem,
+    /// the module the item is declared in, ie: `crate::commands::files` or `crate::commands::files::inline`
+    pub module_path: String,
+    pub file: PathBuf,
+    /// where the name of the item is in `file`, lines start at 1 and columns at 0
+    pub span: LineColumn,
+}
+
+impl MessageStruct {
+    /// `file:line:column` of the item, the way rustc reports locations
+    pub fn location(&self) -> String {
+        location(&self.file, self.span)
+    }
+}
+
+pub enum MessageItem {
+    Struct(ItemStruct),
+    Enum(ItemEnum),
+}
+
+impl MessageItem {
+    pub fn ident(&self) -> &syn::Ident {
+        match self {
+            MessageItem::Struct(item) => &item.ident,
+            MessageItem::Enum(item) => &item.ident,
+        }
+    }
+
+    pub fn attrs(&self) -> &[syn::Attribute] {
+        match self {
+            MessageItem::Struct(item) => &item.attrs,
+            MessageItem::Enum(item) => &item.attrs,
+        }
+    }
+
+    pub fn generics(&self) -> &syn::Generics {
+        match self {
+            MessageItem::Struct(item) => &item.generics,
+            MessageItem::Enum(item) => &item.generics,
+        }
+    }
+}
+
+/// An `impl TauriCommand<Response> for Command` or `impl TauriStreamCommand<Item> for Command` block
+/// marked with `#[tauri_command_impl]`.
+///
+/// Generic commands give one `CommandImpl` for every type in their `instances`.
+pub struct CommandImpl {
+    pub struct_name: syn::Ident,
+    /// the type of the command, ie: `ListPage<File>` for an instance of `ListPage<T>`
+    pub self_ty: Type,
+    /// the index of the instance for generic commands
+    pub instance: Option<usize>,
+    pub command_name: String,
+    /// whether `COMMAND_NAME` is written out in the impl block, otherwise it comes from `#[tauri_command]`
+    pub explicit_name: bool,
+    /// the response type, or the item type of a streaming command
+    pub response_type: Type,
+    pub error_type: Option<Type>,
+    /// whether this is a `TauriStreamCommand`
+    pub streaming: bool,
+    pub module_path: String,
+    pub file: PathBuf,
+    /// where the self type is in `file`, lines start at 1 and columns at 0
+    pub span: LineColumn,
+    pub item: ItemImpl,
+}
+
+impl CommandImpl {
+    /// `file:line:column` of the impl block, the way rustc reports locations
+    pub fn location(&self) -> String {
+        location(&self.file, self.span)
+    }
+}
+
+/// Finds every message struct and command impl in `source_directory`
+/// and writes the command registry to `OUT_DIR/tauri_commands.rs`.
+///
+/// The crate is read by `scan::scan`, its warnings and the files it read are passed on to cargo.
+/// Commands, events or messages with the same name fail the build, see `validate_message_structs`.
+///
+/// Include the registry in the crate root of the backend to get the `tauri_commands!()` macro
+/// and the `TAURI_COMMANDS` list:
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/tauri_commands.rs"));
+///
+/// tauri::Builder::default().invoke_handler(tauri_commands!())
+/// ```
+/// The frontend includes `OUT_DIR/tauri_schema.rs` instead, its `TAURI_SCHEMA` is checked against
+/// the one in the registry of the backend by `slvr_rust_lib::tauri_wasm::schema::check_schema`.
+pub fn resolve_message_structs(source_directory: &'static str) -> MessageStructs {
+    let scan = crate::scan::scan(source_directory);
+    scan.emit_cargo_directives();
+
+    let message_structs = collect_message_structs(scan.items());
+    validate::check_names(&message_structs);
+
+    registry::write_command_registry(&message_structs);
+    schema::write_schema(&message_structs);
+
+    message_structs
+}
+
+/// Like `resolve_message_structs`, for messages spread over several crates of the workspace, ie: a
+/// shared `app_messages` crate the frontend and the backend both depend on:
+/// ```ignore
+/// // build.rs of the backend
+/// resolve_workspace_message_structs(&["app_messages", env!("CARGO_PKG_NAME")]);
+/// ```
+/// The crates are found by `scan::Workspace::discover`. The module paths of items from other crates
+/// start with the crate name instead of `crate`, ie: `app_messages::files::ListFiles`. If the
+/// workspace cannot be read, that is reported as a cargo warning and only the crate being built
+/// is read.
+///
+/// The functions `#[tauri_command_impl]` generates are private to their crate, so the
+/// `tauri_commands!()` macro only dispatches the commands implemented in the crate being built,
+/// commands of other crates are only listed in `TAURI_COMMANDS` and `TAURI_SCHEMA`.
+pub fn resolve_workspace_message_structs(crates: &[&str]) -> MessageStructs {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let scan = workspace_scan(
+        Workspace::discover(),
+        crates,
+        Path::new(&manifest_dir).join("src"),
+    );
+    scan.emit_cargo_directives();
+
+    let package_name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    let items: Vec<ScannedItem> = scan
+        .items()
+        .iter()
+        .cloned()
+        .map(|mut item| {
+            if item.crate_name != package_name {
+                item.module_path =
+                    item.module_path
+                        .replacen("crate", &item.crate_name.replace('-', "_"), 1);
+            }
+            item
+        })
+        .collect();
+    let message_structs = collect_message_structs(&items);
+    validate::check_names(&message_structs);
+
+    registry::write_command_registry(&message_structs);
+    schema::write_schema(&message_structs);
+
+    message_structs
+}
+
+/// the scan of `crates`, or of the crate in `source_directory` alone when the workspace could not be read
+pub(crate) fn workspace_scan(
+    workspace: Result<Workspace, WorkspaceError>,
+    crates: &[&str],
+    source_directory: impl AsRef<Path>,
+) -> Scan {
+    match workspace {
+        Ok(workspace) => workspace.scan(crates),
+        Err(err) => {
+            let mut scan = crate::scan::scan(source_directory);
+            scan.push_warning(format!(
+                "{err}, only the crate being built was read for tauri messages"
+            ));
+            scan
+        }
+    }
+}
+
+/// picks the messages and command impls out of the scanned items
+pub(crate) fn collect_message_structs(items: &[ScannedItem]) -> MessageStructs {
+    let mut message_structs = MessageStructs::default();
+
+    for scanned in items {
+        let item = match &scanned.item {
+            syn::Item::Struct(item_struct) => MessageItem::Struct(item_struct.clone()),
+            syn::Item::Enum(item_enum) => MessageItem::Enum(item_enum.clone()),
+            syn::Item::Impl(item_impl) => {
+                message_structs
+                    .commands
+                    .extend(command_impls(item_impl.clone(), scanned));
+                continue;
+            }
+            _ => continue,
+        };
+
+        let message = MessageStruct {
+            item,
+            module_path: scanned.module_path.clone(),
+            file: scanned.file.clone(),
+            span: scanned.span,
+        };
+        if MESSAGE_ATTRIBUTES
+            .iter()
+            .any(|name| scanned.has_attribute(name))
+        {
+            message_structs.messages.push(message);
+        } else if scanned.has_attribute(EVENT_ATTRIBUTE) {
+            message_structs.events.push(message);
+        }
+    }
+
+    resolve_command_names(&mut message_structs);
+    message_structs
+}
+
+/// gives the commands that leave out `COMMAND_NAME` the name from their `#[tauri_command]`
+pub(crate) fn resolve_command_names(message_structs: &mut MessageStructs) {
+    let MessageStructs {
+        messages, commands, ..
+    } = message_structs;
+
+    for command in commands.iter_mut().filter(|command| !command.explicit_name) {
+        if let Some(message) = command_message(messages, command) {
+            command.command_name = default_command_name(message);
+        }
+    }
+}
+
+/// the `#[tauri_command]` struct of a command impl, preferring a struct in the same module
+/// when several have the same name
+pub(crate) fn command_message<'a>(
+    messages: &'a [MessageStruct],
+    command: &CommandImpl,
+) -> Option<&'a MessageStruct> {
+    let mut candidates = messages.iter().filter(|message| {
+        *message.item.ident() == command.struct_name
+            && message
+                .item
+                .attrs()
+                .iter()
+                .any(|attribute| attribute_is(attribute, COMMAND_ATTRIBUTE))
+    });
+    let same_module = candidates
+        .clone()
+        .find(|message| message.module_path == command.module_path);
+
+    same_module.or_else(|| candidates.next())
+}
+
+/// the name `#[tauri_command]` gives a struct, the snake_case struct name unless `name = "..."` is passed
+fn default_command_name(message: &MessageStruct) -> String {
+    let mut command_name = message.item.ident().unraw().to_string().to_snake_case();
+
+    for attribute in message.item.attrs() {
+        if attribute_is(attribute, COMMAND_ATTRIBUTE)
+            && matches!(attribute.meta, syn::Meta::List(_))
+        {
+            let _ = attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    command_name = meta.value()?.parse::<syn::LitStr>()?.value();
+                }
+                Ok(())
+            });
+        }
+    }
+
+    command_name
+}
+
+/// whether `module_path` is in the crate being built, items of other crates of the workspace
+/// start with the name of their crate
+pub(crate) fn is_local_module(module_path: &str) -> bool {
+    module_path == "crate" || module_path.starts_with("crate::")
+}
+
+/// whether the message attribute passes `binary`, which sends the message with postcard instead of json
+pub(crate) fn is_binary(message: &MessageStruct) -> bool {
+    let mut binary = false;
+
+    for attribute in message.item.attrs() {
+        if MESSAGE_ATTRIBUTES
+            .iter()
+            .any(|name| attribute_is(attribute, name))
+            && matches!(attribute.meta, syn::Meta::List(_))
+        {
+            let _ = attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("binary") {
+                    binary = true;
+                } else if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    binary
+}
+
+/// reads a `#[tauri_command_impl]` block the same way the macro does.
+/// Blocks the macro would reject are skipped, the macro reports those errors itself.
+fn command_impls(item: ItemImpl, scanned: &ScannedItem) -> Vec<CommandImpl> {
+    let Some(attribute) = item
+        .attrs
+        .iter()
+        .find(|attribute| attribute_is(attribute, COMMAND_IMPL_ATTRIBUTE))
+    else {
+        return Vec::new();
+    };
+
+    let mut explicit_response = None;
+    let mut explicit_error = None;
+    let mut instances = Vec::new();
+    if let syn::Meta::List(_) = attribute.meta {
+        let parsed = attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("response") {
+                explicit_response = Some(meta.value()?.parse::<Type>()?);
+            } else if meta.path.is_ident("error") {
+                explicit_error = Some(meta.value()?.parse::<Type>()?);
+            } else if meta.path.is_ident("instances") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                instances.extend(content.parse_terminated(
+                    |input| {
+                        let self_ty: Type = input.parse()?;
+                        input.parse::<syn::Token![=]>()?;
+                        Ok((self_ty, input.parse::<syn::LitStr>()?.value()))
+                    },
+                    syn::Token![,],
+                )?);
+            }
+            Ok(())
+        });
+        if parsed.is_err() {
+            return Vec::new();
+        }
+    }
+
+    let Type::Path(self_path) = &*item.self_ty else {
+        return Vec::new();
+    };
+    let Some(struct_name) = self_path
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident.clone())
+    else {
+        return Vec::new();
+    };
+
+    let explicit_name = item.items.iter().find_map(|impl_item| match impl_item {
+        ImplItem::Const(impl_const) if impl_const.ident == "COMMAND_NAME" => Some(&impl_const.expr),
+        _ => None,
+    });
+    // without `COMMAND_NAME` the name comes from `#[tauri_command]`, see `resolve_command_names`
+    let command_name = match explicit_name {
+        Some(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        })) => lit_str.value(),
+        Some(_) => return Vec::new(),
+        None => struct_name.unraw().to_string().to_snake_case(),
+    };
+
+    let Some((_, trait_path, _)) = item.trait_.as_ref() else {
+        return Vec::new();
+    };
+    let streaming = trait_path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "TauriStreamCommand");
+
+    let Some(response_type) = explicit_response.or_else(|| trait_type_argument(&item, 0)) else {
+        return Vec::new();
+    };
+    let error_type = explicit_error.or_else(|| trait_type_argument(&item, 1));
+
+    let command =
+        |self_ty: Type, instance, command_name, substitutions: &Substitutions| CommandImpl {
+            struct_name: struct_name.clone(),
+            self_ty,
+            instance,
+            command_name,
+            explicit_name: explicit_name.is_some() || instance.is_some(),
+            response_type: substitutions.apply(response_type.clone()),
+            error_type: error_type
+                .clone()
+                .map(|error_type| substitutions.apply(error_type)),
+            streaming,
+            module_path: scanned.module_path.clone(),
+            file: scanned.file.clone(),
+            span: scanned.span,
+            item: item.clone(),
+        };
+
+    // generic commands are only registered for their instances, the macro rejects them without any
+    if item.generics.params.is_empty() {
+        vec![command(
+            (*item.self_ty).clone(),
+            None,
+            command_name,
+            &Substitutions::default(),
+        )]
+    } else {
+        instances
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (instance_ty, _))| match instance_ty {
+                Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == struct_name),
+                _ => false,
+            })
+            // the macro rejects the instances it cannot work out the type parameters of
+            .filter_map(|(index, (instance_ty, command_name))| {
+                let substitutions =
+                    instance_substitutions(&item.generics, &item.self_ty, &instance_ty).ok()?;
+                Some(command(
+                    instance_ty,
+                    Some(index),
+                    command_name,
+                    &substitutions,
+                ))
+            })
+            .collect()
+    }
+}
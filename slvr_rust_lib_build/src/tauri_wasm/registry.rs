@@ -0,0 +1,163 @@
+use crate::tauri_wasm::capabilities::command_windows;
+use crate::tauri_wasm::schema::schema_const;
+use crate::tauri_wasm::{CommandImpl, MessageStructs, is_local_module};
+use quote::{ToTokens, quote};
+use std::path::PathBuf;
+use syn::ext::IdentExt;
+
+pub(crate) const REGISTRY_FILE_NAME: &str = "tauri_commands.rs";
+
+/// writes the generated command registry to `OUT_DIR`
+pub(crate) fn write_command_registry(message_structs: &MessageStructs) {
+    let out_dir = std::env::var("OUT_DIR")
+        .expect("OUT_DIR is not set, is this being called from a build script?");
+    let registry = command_registry(message_structs);
+
+    std::fs::write(PathBuf::from(out_dir).join(REGISTRY_FILE_NAME), registry)
+        .expect("Error while writing command registry");
+}
+
+/// generates the `TAURI_COMMANDS` list, the `TAURI_SCHEMA`, the batch command and the `tauri_commands!()` macro
+pub(crate) fn command_registry(message_structs: &MessageStructs) -> String {
+    let mut infos = Vec::new();
+    let mut batch_arms = Vec::new();
+    let mut dispatch_arms = String::new();
+    // tauri only checks the capabilities of the batch command, so a batch checks the windows of
+    // every command in it, unless no command lists any and there are no generated capabilities
+    let windows_listed = message_structs.commands.iter().any(|command| {
+        is_local_module(&command.module_path)
+            && !command_windows(message_structs, command).is_empty()
+    });
+
+    for command in &message_structs.commands {
+        let struct_name = format!("{}::{}", command.module_path, type_string(&command.self_ty));
+        let command_name = &command.command_name;
+        let response_type = type_string(&command.response_type);
+        let streaming = command.streaming;
+
+        infos.push(quote! {
+            slvr_rust_lib::tauri_wasm::command::TauriCommandInfo {
+                struct_name: #struct_name,
+                command_name: #command_name,
+                response_type: #response_type,
+                streaming: #streaming,
+            }
+        });
+        // commands implemented in other crates of the workspace cannot be reached from here
+        if !is_local_module(&command.module_path) {
+            continue;
+        }
+        let (function, respond_function, wrapper_macro) = command_function_paths(command);
+        // streaming commands need a channel of their own, so they cannot be batched
+        if !streaming {
+            let function: syn::Path =
+                syn::parse_str(&function).expect("generated command path is not valid rust");
+            // plugin commands are allowed by the permissions of their plugin, like in the capabilities
+            let window_check = (windows_listed && !command_name.starts_with("plugin:")).then(|| {
+                let windows = command_windows(message_structs, command);
+                quote! {
+                    slvr_rust_lib::tauri_wasm::batch::check_window(#command_name, &[#(#windows),*], window.label())?;
+                }
+            });
+            batch_arms.push(quote! {
+                #command_name => {
+                    #window_check
+                    #function(slvr_rust_lib::tauri_wasm::batch::batch_args(args)?, app, window, None).await
+                }
+            });
+        }
+        dispatch_arms.push_str(&format!(
+            "            {command_name:?} => {wrapper_macro}!({respond_function}, __tauri_invoke__),\n"
+        ));
+    }
+
+    let schema = schema_const(message_structs);
+    let registry = quote! {
+        /// Every command found by `slvr_rust_lib_build::tauri_wasm::resolve_message_structs`.
+        #[allow(dead_code)]
+        pub const TAURI_COMMANDS: &[slvr_rust_lib::tauri_wasm::command::TauriCommandInfo] = &[#(#infos),*];
+
+        #schema
+
+        /// Handles a `Batch` sent by the frontend, every command goes through its own generated tauri command
+        /// once the window is allowed to send it.
+        #[allow(dead_code, unused_variables)]
+        #[tauri::command]
+        async fn __slvr_batch<R: tauri::Runtime>(
+            args: slvr_rust_lib::tauri_wasm::batch::BatchRequest,
+            app: tauri::AppHandle<R>,
+            window: tauri::WebviewWindow<R>,
+        ) -> Vec<slvr_rust_lib::tauri_wasm::middleware::CommandResult> {
+            slvr_rust_lib::tauri_wasm::batch::run_batch(args, |command_name, args| {
+                let app = app.clone();
+                let window = window.clone();
+                async move {
+                    match command_name.as_str() {
+                        #(#batch_arms)*
+                        _ => Err(slvr_rust_lib::tauri_wasm::batch::unknown_command(&command_name)),
+                    }
+                }
+            })
+            .await
+        }
+    };
+    let file: syn::File =
+        syn::parse2(registry).expect("generated command registry is not valid rust");
+
+    // prettyplease leaves macro bodies as a single line of tokens, so the macro is written by hand
+    format!(
+        "// generated by slvr_rust_lib_build, do not edit\n{}
+/// Expands to the invoke handler of every generated command, use it in place of `tauri::generate_handler!`.
+///
+/// Commands are matched by their `COMMAND_NAME`, which does not have to be a valid rust identifier.
+/// Also handles the batches sent by `Batch::send`, the cancellations sent by `TauriCommand::send_with`
+/// and the handshake sent by `check_schema`, which is answered with `TAURI_SCHEMA`.
+/// Must be used in the crate root, the command paths are relative to it.
+#[allow(unused_macros)]
+macro_rules! tauri_commands {{
+    () => {{
+        move |__tauri_invoke__: tauri::ipc::Invoke<_>| match __tauri_invoke__.message.command() {{
+{dispatch_arms}            slvr_rust_lib::tauri_wasm::batch::BATCH_COMMAND_NAME => {{
+                __cmd____slvr_batch!(__slvr_batch, __tauri_invoke__)
+            }}
+            slvr_rust_lib::tauri_wasm::cancellation::CANCEL_COMMAND_NAME => {{
+                slvr_rust_lib::tauri_wasm::cancellation::cancel_invoke(__tauri_invoke__)
+            }}
+            slvr_rust_lib::tauri_wasm::schema::HANDSHAKE_COMMAND_NAME => {{
+                slvr_rust_lib::tauri_wasm::schema::handshake_invoke(__tauri_invoke__, &TAURI_SCHEMA)
+            }}
+            _ => false,
+        }}
+    }};
+}}
+",
+        prettyplease::unparse(&file)
+    )
+}
+
+/// the paths, relative to the crate root, of the functions `#[tauri_command_impl]` generates to handle
+/// the command and to answer its invoke, and of the tauri wrapper macro it exports to call the latter,
+/// the names must match the macro
+fn command_function_paths(command: &CommandImpl) -> (String, String, String) {
+    let suffix = match command.instance {
+        Some(index) => format!("{}_{index}", command.struct_name.unraw()),
+        None => command.struct_name.unraw().to_string(),
+    };
+    let function = format!("__tauri_command_{suffix}");
+    let respond_function = format!("__tauri_respond_{suffix}");
+    let wrapper_macro = format!("__tauri_invoke_{suffix}");
+
+    match command.module_path.strip_prefix("crate::") {
+        Some(module_path) => (
+            format!("{module_path}::{function}"),
+            format!("{module_path}::{respond_function}"),
+            format!("{module_path}::{wrapper_macro}"),
+        ),
+        None => (function, respond_function, wrapper_macro),
+    }
+}
+
+/// prints a type without the spaces `quote` puts between tokens
+pub(crate) fn type_string(type_: &syn::Type) -> String {
+    type_.to_token_stream().to_string().replace(' ', "")
+}
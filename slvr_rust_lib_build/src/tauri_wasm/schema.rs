@@ -0,0 +1,178 @@
+use crate::tauri_wasm::registry::type_string;
+use crate::tauri_wasm::{CommandImpl, MessageItem, MessageStruct, MessageStructs};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use syn::visit_mut::VisitMut;
+
+pub(crate) const SCHEMA_FILE_NAME: &str = "tauri_schema.rs";
+
+/// writes the `TAURI_SCHEMA` the frontend checks the backend against to `OUT_DIR`
+pub(crate) fn write_schema(message_structs: &MessageStructs) {
+    let out_dir = std::env::var("OUT_DIR")
+        .expect("OUT_DIR is not set, is this being called from a build script?");
+    let file: syn::File =
+        syn::parse2(schema_const(message_structs)).expect("generated schema is not valid rust");
+
+    std::fs::write(
+        PathBuf::from(out_dir).join(SCHEMA_FILE_NAME),
+        format!(
+            "// generated by slvr_rust_lib_build, do not edit\n{}",
+            prettyplease::unparse(&file)
+        ),
+    )
+    .expect("Error while writing schema");
+}
+
+/// the `TAURI_SCHEMA` const, also part of the command registry so the backend can answer the handshake
+pub(crate) fn schema_const(message_structs: &MessageStructs) -> TokenStream {
+    let fingerprint = schema_fingerprint(message_structs);
+    let mut commands: Vec<_> = message_structs
+        .commands
+        .iter()
+        .map(|command| {
+            (
+                command.command_name.as_str(),
+                command_fingerprint(message_structs, command),
+            )
+        })
+        .collect();
+    commands.sort();
+    let commands = commands.iter().map(|(command_name, fingerprint)| {
+        quote! {
+            slvr_rust_lib::tauri_wasm::schema::CommandFingerprint {
+                command_name: #command_name,
+                fingerprint: #fingerprint,
+            }
+        }
+    });
+
+    quote! {
+        /// The fingerprint of every message and command found by `slvr_rust_lib_build::tauri_wasm::resolve_message_structs`.
+        #[allow(dead_code)]
+        pub const TAURI_SCHEMA: slvr_rust_lib::tauri_wasm::schema::TauriSchema =
+            slvr_rust_lib::tauri_wasm::schema::TauriSchema {
+                fingerprint: #fingerprint,
+                commands: &[#(#commands),*],
+            };
+    }
+}
+
+/// A hash over every message definition and command signature, the same on every machine and
+/// for any order the files are found in.
+///
+/// Doc comments and the modules the messages live in are left out, only what changes the
+/// messages sent between the frontend and the backend changes the fingerprint.
+pub fn schema_fingerprint(message_structs: &MessageStructs) -> String {
+    let messages: BTreeSet<String> = message_structs
+        .messages
+        .iter()
+        .map(message_definition)
+        .collect();
+    let commands: BTreeSet<String> = message_structs
+        .commands
+        .iter()
+        .map(command_signature)
+        .collect();
+
+    fingerprint(messages.iter().chain(&commands))
+}
+
+/// a hash over the signature of the command and every message its command, response and error
+/// types refer to, directly or through the fields of other messages
+pub(crate) fn command_fingerprint(
+    message_structs: &MessageStructs,
+    command: &CommandImpl,
+) -> String {
+    let mut pending = type_idents(command.self_ty.to_token_stream());
+    pending.extend(type_idents(command.response_type.to_token_stream()));
+    if let Some(error_type) = &command.error_type {
+        pending.extend(type_idents(error_type.to_token_stream()));
+    }
+
+    let mut seen = HashSet::new();
+    let mut messages = BTreeSet::new();
+    while let Some(ident) = pending.pop() {
+        if !seen.insert(ident.clone()) {
+            continue;
+        }
+        // messages with the same name in different modules are all included, a few false
+        // mismatches are better than a missed one
+        for message in message_structs
+            .messages
+            .iter()
+            .filter(|message| *message.item.ident() == ident)
+        {
+            pending.extend(type_idents(item_tokens(&message.item)));
+            messages.insert(message_definition(message));
+        }
+    }
+
+    fingerprint(std::iter::once(&command_signature(command)).chain(&messages))
+}
+
+fn command_signature(command: &CommandImpl) -> String {
+    format!(
+        "{}:{}:{}:{}:{}",
+        command.command_name,
+        command.streaming,
+        type_string(&command.self_ty),
+        type_string(&command.response_type),
+        command
+            .error_type
+            .as_ref()
+            .map(type_string)
+            .unwrap_or_default(),
+    )
+}
+
+/// the message without its doc comments, as tokens printed on one line
+fn message_definition(message: &MessageStruct) -> String {
+    struct StripDocs;
+
+    impl VisitMut for StripDocs {
+        fn visit_attributes_mut(&mut self, attributes: &mut Vec<syn::Attribute>) {
+            attributes.retain(|attribute| !attribute.path().is_ident("doc"));
+        }
+    }
+
+    let mut item = match &message.item {
+        MessageItem::Struct(item) => syn::Item::Struct(item.clone()),
+        MessageItem::Enum(item) => syn::Item::Enum(item.clone()),
+    };
+    StripDocs.visit_item_mut(&mut item);
+    item.to_token_stream().to_string()
+}
+
+fn item_tokens(item: &MessageItem) -> TokenStream {
+    match item {
+        MessageItem::Struct(item) => item.to_token_stream(),
+        MessageItem::Enum(item) => item.to_token_stream(),
+    }
+}
+
+/// every identifier in `tokens`, which is more than the types but never misses one
+fn type_idents(tokens: TokenStream) -> Vec<syn::Ident> {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) => vec![ident],
+            TokenTree::Group(group) => type_idents(group.stream()),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// FNV-1a, `DefaultHasher` may change between rust versions and the two builds can use different ones
+fn fingerprint<'a>(parts: impl IntoIterator<Item = &'a String>) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        // the separator keeps `["ab", "c"]` and `["a", "bc"]` apart
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
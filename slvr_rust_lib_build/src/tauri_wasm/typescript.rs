@@ -0,0 +1,651 @@
+use crate::tauri_wasm::{CommandImpl, MessageItem, MessageStructs, is_binary};
+use std::collections::HashSet;
+use std::path::Path;
+use syn::{Fields, GenericArgument, PathArguments, Type};
+
+pub(crate) const DECLARATIONS_FILE_NAME: &str = "tauri_messages.d.ts";
+pub(crate) const BINDINGS_FILE_NAME: &str = "tauri_messages.js";
+
+/// Writes typescript bindings for every message and command into `output_directory`.
+///
+/// `tauri_messages.d.ts` holds an interface (or type alias) for every message and a typed
+/// `invoke` wrapper for every command, `tauri_messages.js` holds the wrappers themselves.
+/// Serde's `rename`, `rename_all`, `tag`, `content`, `untagged`, `flatten` and `skip` attributes
+/// are applied the same way serde applies them. Commands whose response is marked `binary` resolve
+/// with the postcard encoded response as an `ArrayBuffer`. Types that are neither messages nor
+/// standard, `chrono`, `time`, `uuid` or `url` types the generator knows are typed `unknown`.
+pub fn write_typescript_bindings(
+    message_structs: &MessageStructs,
+    output_directory: impl AsRef<Path>,
+) {
+    let output_directory = output_directory.as_ref();
+
+    std::fs::create_dir_all(output_directory)
+        .expect("Error while creating typescript output directory");
+    std::fs::write(
+        output_directory.join(DECLARATIONS_FILE_NAME),
+        typescript_declarations(message_structs),
+    )
+    .expect("Error while writing typescript declarations");
+    std::fs::write(
+        output_directory.join(BINDINGS_FILE_NAME),
+        javascript_bindings(message_structs),
+    )
+    .expect("Error while writing javascript bindings");
+}
+
+pub(crate) fn typescript_declarations(message_structs: &MessageStructs) -> String {
+    let mut output = String::from(
+        "// generated by slvr_rust_lib_build, do not edit\n\n\
+         /** The form a backend error takes when a command rejects. */\n\
+         export interface BackendError<E> {\n    backend_error: E;\n}\n",
+    );
+
+    // types that are neither declared here nor known to `ts_type` become `unknown`
+    let declared: HashSet<String> = message_structs
+        .messages
+        .iter()
+        .map(|message| message.item.ident().to_string())
+        .collect();
+    let declared = &declared;
+
+    for message in &message_structs.messages {
+        output.push('\n');
+        output.push_str(&message_declaration(&message.item, declared));
+    }
+
+    let binary_messages: Vec<&syn::Ident> = message_structs
+        .messages
+        .iter()
+        .filter(|message| is_binary(message))
+        .map(|message| message.item.ident())
+        .collect();
+
+    for command in &message_structs.commands {
+        let error = match &command.error_type {
+            Some(error_type) => ts_type(error_type, declared),
+            None => String::from("never"),
+        };
+        if command.streaming {
+            output.push_str(&format!(
+                "\n/** Invokes `{command_name}` and calls `onItem` with every item it sends, \
+                 rejects with `BackendError<{error}>` or the tauri error message. */\n\
+                 export declare function {function}(args: {args}, onItem: (item: {item}) => void): Promise<void>;\n",
+                command_name = command.command_name,
+                function = function_name(command),
+                args = ts_type(&command.self_ty, declared),
+                item = ts_type(&command.response_type, declared),
+            ));
+        } else if type_ident(&command.response_type)
+            .is_some_and(|ident| binary_messages.contains(&ident))
+        {
+            output.push_str(&format!(
+                "\n/** Invokes `{command_name}`, resolves with the postcard encoded `{response}`, \
+                 rejects with `BackendError<{error}>` or the tauri error message. */\n\
+                 export declare function {function}(args: {args}): Promise<ArrayBuffer>;\n",
+                command_name = command.command_name,
+                function = function_name(command),
+                args = ts_type(&command.self_ty, declared),
+                response = ts_type(&command.response_type, declared),
+            ));
+        } else {
+            output.push_str(&format!(
+                "\n/** Invokes `{command_name}`, rejects with `BackendError<{error}>` or the tauri error message. */\n\
+                 export declare function {function}(args: {args}): Promise<{response}>;\n",
+                command_name = command.command_name,
+                function = function_name(command),
+                args = ts_type(&command.self_ty, declared),
+                response = ts_type(&command.response_type, declared),
+            ));
+        }
+    }
+
+    output
+}
+
+pub(crate) fn javascript_bindings(message_structs: &MessageStructs) -> String {
+    let mut output = String::from("// generated by slvr_rust_lib_build, do not edit\n");
+
+    for command in &message_structs.commands {
+        if command.streaming {
+            // the items arrive as `StreamMessage`s, `End` only matters to the rust frontend
+            output.push_str(&format!(
+                "\nexport function {function}(args, onItem) {{\n    \
+                 const channel = new window.__TAURI__.core.Channel();\n    \
+                 channel.onmessage = (message) => {{\n        \
+                 if (message.kind === \"Item\") {{\n            onItem(message.value);\n        }}\n    }};\n    \
+                 return window.__TAURI__.core.invoke({command_name:?}, {{ args, channel }});\n}}\n",
+                function = function_name(command),
+                command_name = command.command_name,
+            ));
+        } else {
+            output.push_str(&format!(
+                "\nexport function {function}(args) {{\n    return window.__TAURI__.core.invoke({command_name:?}, {{ args }});\n}}\n",
+                function = function_name(command),
+                command_name = command.command_name,
+            ));
+        }
+    }
+
+    output
+}
+
+/// the name of a type without its generic arguments, only the outer type decides how a response is encoded
+fn type_ident(rust_type: &Type) -> Option<&syn::Ident> {
+    match rust_type {
+        Type::Path(type_path) => type_path.path.segments.last().map(|segment| &segment.ident),
+        _ => None,
+    }
+}
+
+/// the name of the invoke wrapper, `COMMAND_NAME` in camelCase
+pub(crate) fn function_name(command: &CommandImpl) -> String {
+    let snake_case: String = command
+        .command_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    RenameRule::CamelCase.apply_to_field(&snake_case)
+}
+
+/// declares the message under its rust name, the name the fields and commands refer to it by,
+/// a container `rename` only changes the name serde reports and not the json
+fn message_declaration(item: &MessageItem, declared: &HashSet<String>) -> String {
+    let attributes = SerdeAttributes::from_attrs(item.attrs());
+    let name = item.ident().to_string();
+    let generics = ts_generics(item.generics());
+    let mut declared = declared.clone();
+    declared.extend(
+        item.generics()
+            .type_params()
+            .map(|param| param.ident.to_string()),
+    );
+    let declared = &declared;
+
+    match item {
+        MessageItem::Struct(item_struct) => match &item_struct.fields {
+            Fields::Named(_) if !attributes.transparent => {
+                let rename_all = RenameRule::from_attribute(attributes.rename_all.as_deref());
+                let (members, flattened) = ts_fields(&item_struct.fields, rename_all, declared);
+                let members: String = members
+                    .iter()
+                    .map(|member| format!("    {member};\n"))
+                    .collect();
+
+                if flattened.is_empty() {
+                    format!("export interface {name}{generics} {{\n{members}}}\n")
+                } else {
+                    format!(
+                        "export type {name}{generics} = {{\n{members}}} & {};\n",
+                        flattened.join(" & ")
+                    )
+                }
+            }
+            fields => format!(
+                "export type {name}{generics} = {};\n",
+                ts_unnamed_fields(fields, declared)
+            ),
+        },
+        MessageItem::Enum(item_enum) => {
+            let variants: Vec<String> = item_enum
+                .variants
+                .iter()
+                .filter_map(|variant| ts_variant(variant, &attributes, declared))
+                .collect();
+            let union = if variants.is_empty() {
+                String::from("never")
+            } else {
+                variants.join("\n    | ")
+            };
+            format!("export type {name}{generics} =\n    | {union};\n")
+        }
+    }
+}
+
+/// returns the members of an object type and the types of any flattened fields
+fn ts_fields(
+    fields: &Fields,
+    rename_all: RenameRule,
+    declared: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut members = Vec::new();
+    let mut flattened = Vec::new();
+
+    for field in fields {
+        let attributes = SerdeAttributes::from_attrs(&field.attrs);
+        if attributes.skip {
+            continue;
+        }
+
+        if attributes.flatten {
+            flattened.push(ts_type(&field.ty, declared));
+            continue;
+        }
+
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let name = attributes.rename.unwrap_or_else(|| {
+            rename_all.apply_to_field(ident.to_string().trim_start_matches("r#"))
+        });
+
+        let (optional, field_type) = match option_inner(&field.ty) {
+            Some(inner) => (true, format!("{} | null", ts_type(inner, declared))),
+            None => (attributes.optional, ts_type(&field.ty, declared)),
+        };
+        let optional = if optional { "?" } else { "" };
+
+        members.push(format!("{}{optional}: {field_type}", ts_property(&name)));
+    }
+
+    (members, flattened)
+}
+
+/// the type of a tuple struct, newtype or unit struct
+fn ts_unnamed_fields(fields: &Fields, declared: &HashSet<String>) -> String {
+    let types: Vec<String> = fields
+        .iter()
+        .filter(|field| !SerdeAttributes::from_attrs(&field.attrs).skip)
+        .map(|field| ts_type(&field.ty, declared))
+        .collect();
+
+    match (fields, types.len()) {
+        (Fields::Unit, _) => String::from("null"),
+        // serde serializes newtypes as their inner value
+        (Fields::Unnamed(unnamed), 1) if unnamed.unnamed.len() == 1 => types[0].clone(),
+        (Fields::Named(_), 1) => types[0].clone(),
+        _ => format!("[{}]", types.join(", ")),
+    }
+}
+
+fn ts_variant(
+    variant: &syn::Variant,
+    enum_attributes: &SerdeAttributes,
+    declared: &HashSet<String>,
+) -> Option<String> {
+    let attributes = SerdeAttributes::from_attrs(&variant.attrs);
+    if attributes.skip {
+        return None;
+    }
+
+    let name = attributes.rename.clone().unwrap_or_else(|| {
+        RenameRule::from_attribute(enum_attributes.rename_all.as_deref())
+            .apply_to_variant(&variant.ident.to_string())
+    });
+    let fields_rule = RenameRule::from_attribute(
+        attributes
+            .rename_all
+            .as_deref()
+            .or(enum_attributes.rename_all_fields.as_deref()),
+    );
+
+    let content = match &variant.fields {
+        Fields::Named(_) => {
+            let (members, flattened) = ts_fields(&variant.fields, fields_rule, declared);
+            let object = if members.is_empty() {
+                String::from("{}")
+            } else {
+                format!("{{ {} }}", members.join("; "))
+            };
+            if flattened.is_empty() {
+                Some(object)
+            } else {
+                Some(format!("{object} & {}", flattened.join(" & ")))
+            }
+        }
+        Fields::Unnamed(_) => Some(ts_unnamed_fields(&variant.fields, declared)),
+        Fields::Unit => None,
+    };
+    let quoted_name = format!("{name:?}");
+
+    let variant_type = if enum_attributes.untagged {
+        content.unwrap_or_else(|| String::from("null"))
+    } else if let Some(tag) = &enum_attributes.tag {
+        let tag = ts_property(tag);
+        match (&enum_attributes.content, content) {
+            (Some(content_name), Some(content)) => {
+                format!(
+                    "{{ {tag}: {quoted_name}; {}: {content} }}",
+                    ts_property(content_name)
+                )
+            }
+            (None, Some(content)) => format!("{{ {tag}: {quoted_name} }} & {content}"),
+            (_, None) => format!("{{ {tag}: {quoted_name} }}"),
+        }
+    } else {
+        match content {
+            Some(content) => format!("{{ {}: {content} }}", ts_property(&name)),
+            None => quoted_name,
+        }
+    };
+
+    Some(variant_type)
+}
+
+fn ts_generics(generics: &syn::Generics) -> String {
+    let params: Vec<String> = generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// quotes property names that are not valid javascript identifiers
+fn ts_property(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+fn option_inner(field_type: &Type) -> Option<&Type> {
+    let Type::Path(path) = field_type else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    type_arguments(&segment.arguments).into_iter().next()
+}
+
+fn type_arguments(arguments: &PathArguments) -> Vec<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(argument_type) => Some(argument_type),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// maps a rust type to the typescript type of its serde representation
+pub(crate) fn ts_type(rust_type: &Type, declared: &HashSet<String>) -> String {
+    match rust_type {
+        Type::Reference(reference) => ts_type(&reference.elem, declared),
+        Type::Paren(paren) => ts_type(&paren.elem, declared),
+        Type::Group(group) => ts_type(&group.elem, declared),
+        Type::Array(array) => format!("{}[]", ts_element_type(&array.elem, declared)),
+        Type::Slice(slice) => format!("{}[]", ts_element_type(&slice.elem, declared)),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => String::from("null"),
+        Type::Tuple(tuple) => format!(
+            "[{}]",
+            tuple
+                .elems
+                .iter()
+                .map(|element| ts_type(element, declared))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return String::from("unknown");
+            };
+            let arguments = type_arguments(&segment.arguments);
+            let argument = |index: usize| {
+                arguments
+                    .get(index)
+                    .map(|argument| ts_type(argument, declared))
+                    .unwrap_or_else(|| String::from("unknown"))
+            };
+
+            match segment.ident.to_string().as_str() {
+                "bool" => String::from("boolean"),
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" | "f32" | "f64" | "NonZeroU8" | "NonZeroU16" | "NonZeroU32"
+                | "NonZeroU64" | "NonZeroUsize" => String::from("number"),
+                "String" | "str" | "char" | "PathBuf" | "Path" | "OsString" | "Uuid" | "Url"
+                | "DateTime" | "NaiveDate" | "NaiveDateTime" | "NaiveTime" => {
+                    String::from("string")
+                }
+                // the `time` types are tuples unless its `serde-human-readable` feature is enabled
+                "Date" => String::from("[number, number]"),
+                "Time" => String::from("[number, number, number, number]"),
+                "PrimitiveDateTime" => {
+                    String::from("[number, number, number, number, number, number]")
+                }
+                "OffsetDateTime" => String::from(
+                    "[number, number, number, number, number, number, number, number, number]",
+                ),
+                "Option" => format!("{} | null", argument(0)),
+                "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "BinaryHeap" => {
+                    let element = arguments
+                        .first()
+                        .map(|argument| ts_element_type(argument, declared))
+                        .unwrap_or_else(|| String::from("unknown"));
+                    format!("{element}[]")
+                }
+                "HashMap" | "BTreeMap" | "IndexMap" => {
+                    format!("Record<{}, {}>", argument(0), argument(1))
+                }
+                "Box" | "Rc" | "Arc" | "Cow" | "Cell" | "RefCell" | "Mutex" | "RwLock" => {
+                    argument(arguments.len().saturating_sub(1))
+                }
+                "Result" => format!("{{ Ok: {} }} | {{ Err: {} }}", argument(0), argument(1)),
+                "Value" => String::from("unknown"),
+                ident if !declared.contains(ident) => String::from("unknown"),
+                ident => {
+                    if arguments.is_empty() {
+                        ident.to_string()
+                    } else {
+                        let arguments: Vec<String> = arguments
+                            .iter()
+                            .map(|argument| ts_type(argument, declared))
+                            .collect();
+                        format!("{ident}<{}>", arguments.join(", "))
+                    }
+                }
+            }
+        }
+        _ => String::from("unknown"),
+    }
+}
+
+/// element types that are unions need parentheses before `[]`
+fn ts_element_type(rust_type: &Type, declared: &HashSet<String>) -> String {
+    let element = ts_type(rust_type, declared);
+    if element.contains(" | ") {
+        format!("({element})")
+    } else {
+        element
+    }
+}
+
+/// The subset of serde attributes that change the shape of the serialized value.
+#[derive(Default)]
+pub(crate) struct SerdeAttributes {
+    pub(crate) rename: Option<String>,
+    pub(crate) rename_all: Option<String>,
+    pub(crate) rename_all_fields: Option<String>,
+    pub(crate) tag: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) untagged: bool,
+    pub(crate) transparent: bool,
+    pub(crate) skip: bool,
+    pub(crate) flatten: bool,
+    /// the field may be missing, ie: `default` or `skip_serializing_if`
+    pub(crate) optional: bool,
+}
+
+impl SerdeAttributes {
+    pub(crate) fn from_attrs(attrs: &[syn::Attribute]) -> Self {
+        let mut attributes = SerdeAttributes::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("serde") {
+                continue;
+            }
+
+            // attributes that don't parse are left for serde to complain about
+            let _ = attr.parse_nested_meta(|meta| {
+                let name = meta
+                    .path
+                    .get_ident()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_default();
+
+                match name.as_str() {
+                    "rename" | "rename_all" | "rename_all_fields" => {
+                        let value = if meta.input.peek(syn::Token![=]) {
+                            Some(meta.value()?.parse::<syn::LitStr>()?.value())
+                        } else {
+                            // `rename(serialize = "..", deserialize = "..")`, the serialized name wins
+                            let mut serialize = None;
+                            meta.parse_nested_meta(|inner| {
+                                let value = inner.value()?.parse::<syn::LitStr>()?.value();
+                                if inner.path.is_ident("serialize") {
+                                    serialize = Some(value);
+                                }
+                                Ok(())
+                            })?;
+                            serialize
+                        };
+                        match name.as_str() {
+                            "rename" => attributes.rename = value,
+                            "rename_all" => attributes.rename_all = value,
+                            _ => attributes.rename_all_fields = value,
+                        }
+                    }
+                    "tag" => attributes.tag = Some(meta.value()?.parse::<syn::LitStr>()?.value()),
+                    "content" => {
+                        attributes.content = Some(meta.value()?.parse::<syn::LitStr>()?.value())
+                    }
+                    "untagged" => attributes.untagged = true,
+                    "transparent" => attributes.transparent = true,
+                    "skip" | "skip_serializing" => attributes.skip = true,
+                    "flatten" => attributes.flatten = true,
+                    "default" | "skip_serializing_if" => {
+                        attributes.optional = true;
+                        skip_meta_value(&meta)?;
+                    }
+                    _ => skip_meta_value(&meta)?,
+                }
+                Ok(())
+            });
+        }
+
+        attributes
+    }
+}
+
+/// consumes the value of an attribute we don't care about so parsing can continue
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_meta_value(&inner))?;
+    }
+    Ok(())
+}
+
+/// Serde's `rename_all` rules, applied the same way serde applies them.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    None,
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl RenameRule {
+    pub(crate) fn from_attribute(rule: Option<&str>) -> Self {
+        match rule {
+            Some("lowercase") => RenameRule::LowerCase,
+            Some("UPPERCASE") => RenameRule::UpperCase,
+            Some("PascalCase") => RenameRule::PascalCase,
+            Some("camelCase") => RenameRule::CamelCase,
+            Some("snake_case") => RenameRule::SnakeCase,
+            Some("SCREAMING_SNAKE_CASE") => RenameRule::ScreamingSnakeCase,
+            Some("kebab-case") => RenameRule::KebabCase,
+            Some("SCREAMING-KEBAB-CASE") => RenameRule::ScreamingKebabCase,
+            _ => RenameRule::None,
+        }
+    }
+
+    /// variants are written in PascalCase
+    pub(crate) fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::None | RenameRule::PascalCase => variant.to_string(),
+            RenameRule::LowerCase => variant.to_ascii_lowercase(),
+            RenameRule::UpperCase => variant.to_ascii_uppercase(),
+            RenameRule::CamelCase => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::SnakeCase => {
+                let mut snake = String::new();
+                for (index, c) in variant.char_indices() {
+                    if index > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnakeCase => RenameRule::SnakeCase
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::KebabCase => RenameRule::SnakeCase
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebabCase => RenameRule::ScreamingSnakeCase
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+
+    /// fields are written in snake_case
+    pub(crate) fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::None | RenameRule::LowerCase | RenameRule::SnakeCase => field.to_string(),
+            RenameRule::UpperCase | RenameRule::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            RenameRule::PascalCase => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(c);
+                    }
+                }
+                pascal
+            }
+            RenameRule::CamelCase => {
+                let pascal = RenameRule::PascalCase.apply_to_field(field);
+                RenameRule::CamelCase.apply_to_variant(&pascal)
+            }
+            RenameRule::KebabCase => field.replace('_', "-"),
+            RenameRule::ScreamingKebabCase => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
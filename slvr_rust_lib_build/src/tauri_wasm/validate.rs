@@ -0,0 +1,276 @@
+use crate::scan::attribute_is;
+use crate::tauri_wasm::registry::type_string;
+use crate::tauri_wasm::typescript::function_name;
+use crate::tauri_wasm::{BUILTIN_COMMANDS, EVENT_ATTRIBUTE, MessageStruct, MessageStructs};
+use slvr_rust_lib_macro_support::to_snake_case::ToSnakeCase;
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use syn::ext::IdentExt;
+
+/// Two or more items that tauri, or the generated code, cannot tell apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameConflict {
+    pub kind: ConflictKind,
+    pub name: String,
+    /// `file:line:column` of every item with the name
+    pub locations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// tauri only calls one of the commands with the same `COMMAND_NAME`
+    CommandName,
+    /// `tauri_commands!()` handles the batch, cancellation and handshake commands itself
+    BuiltinCommandName,
+    /// the typescript bindings export one function for every command, named by its `COMMAND_NAME`
+    /// in camelCase
+    FunctionName,
+    /// the function the typescript bindings export for a command is not a javascript identifier
+    InvalidFunctionName,
+    /// listeners get the events of every struct with the same `EVENT_NAME`
+    EventName,
+    /// the typescript bindings declare every message by its name
+    StructName,
+}
+
+impl Display for NameConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ConflictKind::CommandName => write!(
+                f,
+                "the command name `{}` is used by more than one command",
+                self.name
+            )?,
+            ConflictKind::BuiltinCommandName => write!(
+                f,
+                "the command name `{}` is taken by a command `tauri_commands!()` handles itself",
+                self.name
+            )?,
+            ConflictKind::FunctionName => write!(
+                f,
+                "the typescript function `{}` is exported for more than one command, rename one of them",
+                self.name
+            )?,
+            ConflictKind::InvalidFunctionName => write!(
+                f,
+                "the typescript function `{}` exported for a command is not a valid javascript \
+                 identifier, rename the command",
+                self.name
+            )?,
+            ConflictKind::EventName => write!(
+                f,
+                "the event name `{}` is used by more than one event",
+                self.name
+            )?,
+            ConflictKind::StructName => write!(
+                f,
+                "the message `{}` is exported more than once, rename one of them",
+                self.name
+            )?,
+        }
+        for location in &self.locations {
+            write!(f, "\n  --> {location}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for NameConflict {}
+
+/// Finds the commands with the same `COMMAND_NAME`, the commands named like the batch, cancellation
+/// or handshake command, the events with the same `EVENT_NAME` and the messages with the same name,
+/// which are exported side by side in the typescript bindings, as are the functions invoking the
+/// commands, which must not share a name either and have to be valid javascript identifiers.
+///
+/// `resolve_message_structs` fails the build when there are any.
+pub fn validate_message_structs(message_structs: &MessageStructs) -> Result<(), Vec<NameConflict>> {
+    let mut conflicts = Vec::new();
+
+    // the instances of a generic command are told apart by their type, they share the impl block
+    let commands = || {
+        message_structs.commands.iter().map(|command| {
+            (
+                command.command_name.clone(),
+                command.location(),
+                type_string(&command.self_ty),
+            )
+        })
+    };
+    conflicts.extend(conflicts_of(ConflictKind::CommandName, commands(), 2));
+    conflicts.extend(conflicts_of(
+        ConflictKind::BuiltinCommandName,
+        commands().filter(|(name, _, _)| BUILTIN_COMMANDS.contains(&name.as_str())),
+        1,
+    ));
+    // a command name used twice is reported above, so only its first command counts here
+    let mut command_names = HashSet::new();
+    conflicts.extend(conflicts_of(
+        ConflictKind::FunctionName,
+        message_structs
+            .commands
+            .iter()
+            .filter(|command| command_names.insert(&command.command_name))
+            .map(|command| {
+                (
+                    function_name(command),
+                    command.location(),
+                    command.command_name.clone(),
+                )
+            }),
+        2,
+    ));
+    conflicts.extend(conflicts_of(
+        ConflictKind::InvalidFunctionName,
+        message_structs
+            .commands
+            .iter()
+            .map(|command| {
+                (
+                    function_name(command),
+                    command.location(),
+                    type_string(&command.self_ty),
+                )
+            })
+            .filter(|(function, _, _)| !is_function_name(function)),
+        1,
+    ));
+    conflicts.extend(conflicts_of(
+        ConflictKind::EventName,
+        message_structs
+            .events
+            .iter()
+            .map(|event| (event_name(event), event.location(), String::new())),
+        2,
+    ));
+    conflicts.extend(conflicts_of(
+        ConflictKind::StructName,
+        message_structs.messages.iter().map(|message| {
+            (
+                message.item.ident().to_string(),
+                message.location(),
+                String::new(),
+            )
+        }),
+        2,
+    ));
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// panics with every conflict, which fails the build script with their locations
+pub(crate) fn check_names(message_structs: &MessageStructs) {
+    if let Err(conflicts) = validate_message_structs(message_structs) {
+        let conflicts: Vec<String> = conflicts.iter().map(ToString::to_string).collect();
+        panic!(
+            "Conflicting names in the tauri messages:\n{}",
+            conflicts.join("\n")
+        );
+    }
+}
+
+/// the names taken by at least `min_items` items, told apart by their location and by what tells
+/// the items declared at the same location apart
+fn conflicts_of(
+    kind: ConflictKind,
+    names: impl Iterator<Item = (String, String, String)>,
+    min_items: usize,
+) -> Vec<NameConflict> {
+    let mut items: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for (name, location, identity) in names {
+        let items = items.entry(name).or_default();
+        if !items.contains(&(location.clone(), identity.clone())) {
+            items.push((location, identity));
+        }
+    }
+
+    items
+        .into_iter()
+        .filter(|(_, items)| items.len() >= min_items)
+        .map(|(name, items)| NameConflict {
+            kind,
+            name,
+            locations: items.into_iter().map(|(location, _)| location).collect(),
+        })
+        .collect()
+}
+
+/// whether `name` can be declared as a javascript function
+fn is_function_name(name: &str) -> bool {
+    const RESERVED: [&str; 46] = [
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "with",
+        "yield",
+    ];
+
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && !RESERVED.contains(&name)
+}
+
+/// the name `#[tauri_event]` gives a struct, the snake_case struct name unless `name = "..."` is passed
+fn event_name(event: &MessageStruct) -> String {
+    let mut event_name = event.item.ident().unraw().to_string().to_snake_case();
+
+    for attribute in event.item.attrs() {
+        if attribute_is(attribute, EVENT_ATTRIBUTE) && matches!(attribute.meta, syn::Meta::List(_))
+        {
+            let _ = attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    event_name = meta.value()?.parse::<syn::LitStr>()?.value();
+                }
+                Ok(())
+            });
+        }
+    }
+
+    event_name
+}
@@ -0,0 +1,126 @@
+use crate::tauri_wasm::capabilities::capability_files;
+use crate::tauri_wasm::write_capabilities;
+use crate::test::{collect_source, source_directory};
+
+const COMMANDS: &str = r#"
+    #[tauri_command(permission = "files", windows = ["main", "settings"])]
+    pub struct ReadFile;
+
+    #[tauri_command(permission = "files", windows = ["main"])]
+    pub struct WriteFile;
+
+    #[tauri_command(name = "Ping", windows = ["main"])]
+    pub struct Ping;
+
+    #[tauri_command]
+    pub struct Forgotten;
+
+    #[tauri_command_impl]
+    impl TauriCommand<Done> for ReadFile {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Done> for WriteFile {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Done> for Ping {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Done> for Forgotten {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Done> for PluginCommand {
+        const COMMAND_NAME: &'static str = "plugin:files|watch";
+    }
+"#;
+
+#[test]
+fn permissions_group_commands() {
+    let files = capability_files(&collect_source(COMMANDS, "commands.rs"));
+
+    assert_eq!(
+        files.permissions.keys().collect::<Vec<_>>(),
+        ["allow-forgotten", "allow-ping", "files", "slvr-builtin"]
+    );
+    assert!(files.permissions["files"].ends_with(
+        "[[permission]]\nidentifier = \"files\"\ndescription = \"Allows `read_file`, `write_file`.\"\n\
+         commands.allow = [\"read_file\", \"write_file\"]\n"
+    ));
+    assert!(files.permissions["allow-ping"].contains("commands.allow = [\"Ping\"]"));
+}
+
+#[test]
+fn capabilities_grant_windows_their_commands() {
+    let files = capability_files(&collect_source(COMMANDS, "commands.rs"));
+
+    assert_eq!(
+        files.capabilities.keys().collect::<Vec<_>>(),
+        ["commands-main", "commands-settings"]
+    );
+    assert!(files.capabilities["commands-main"].contains(
+        "\"windows\": [\"main\"],\n  \"permissions\": [\n    \"slvr-builtin\",\n    \"allow-ping\",\n    \"files\"\n  ]"
+    ));
+    assert!(files.capabilities["commands-settings"].contains("\"slvr-builtin\",\n    \"files\"\n"));
+
+    assert_eq!(files.warnings.len(), 1);
+    assert!(
+        files.warnings[0]
+            .starts_with("src/commands.rs: no window may send the command `forgotten`")
+    );
+}
+
+#[test]
+fn windows_with_the_same_identifier_conflict() {
+    let files = capability_files(&collect_source(
+        r#"
+        #[tauri_command(windows = ["main-window"])]
+        pub struct Open;
+
+        #[tauri_command(windows = ["main_window"])]
+        pub struct Close;
+
+        #[tauri_command_impl]
+        impl TauriCommand<Done> for Open {}
+
+        #[tauri_command_impl]
+        impl TauriCommand<Done> for Close {}
+        "#,
+        "commands.rs",
+    ));
+
+    assert_eq!(
+        files.conflicts,
+        [
+            "the windows `main-window` and `main_window` would both get the capability `commands-main-window`"
+        ]
+    );
+    assert!(files.permissions["slvr-builtin"].contains(
+        "commands.allow = [\"__slvr_batch\", \"__slvr_handshake\", \"__tauri_wasm_cancel\"]"
+    ));
+}
+
+#[test]
+fn only_generated_files_are_replaced() {
+    let directory = source_directory(
+        "capabilities",
+        &[
+            ("permissions/files.toml", "# written by hand"),
+            (
+                "permissions/removed.toml",
+                "# generated by slvr_rust_lib_build from the `permission` of the tauri commands, do not edit\n",
+            ),
+            ("capabilities/default.json", "{}"),
+        ],
+    );
+    write_capabilities(&collect_source(COMMANDS, "commands.rs"), &directory);
+
+    let read = |file: &str| std::fs::read_to_string(directory.join(file)).ok();
+    assert_eq!(
+        read("permissions/files.toml").as_deref(),
+        Some("# written by hand")
+    );
+    assert_eq!(read("permissions/removed.toml"), None);
+    assert!(read("permissions/allow-ping.toml").is_some());
+    assert_eq!(read("capabilities/default.json").as_deref(), Some("{}"));
+    assert!(read("capabilities/commands-main.json").is_some());
+    let _ = std::fs::remove_dir_all(directory);
+}
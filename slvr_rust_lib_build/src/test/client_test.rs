@@ -0,0 +1,115 @@
+use crate::scan::{ActiveCfg, Scanner};
+use crate::tauri_wasm::client::frontend_client;
+use crate::tauri_wasm::collect_message_structs;
+use crate::test::source_directory;
+
+const BACKEND: &[(&str, &str)] = &[
+    (
+        "lib.rs",
+        r#"
+        mod commands;
+        mod state;
+
+        use slvr_rust_lib::{tauri_command, tauri_command_impl, tauri_response};
+        use slvr_rust_lib::tauri_wasm::command::TauriCommand;
+        use std::sync::Mutex;
+
+        #[tauri_response]
+        pub struct Done;
+        "#,
+    ),
+    (
+        "commands.rs",
+        r#"
+        use crate::Done;
+        use crate::state::AppState;
+        use slvr_rust_lib::prelude::*;
+        use std::path::{Path, PathBuf};
+        use tauri::Manager;
+
+        #[cfg(feature = "files")]
+        #[tauri_command]
+        pub struct OpenFile {
+            pub path: PathBuf,
+            #[cfg(feature = "files")]
+            pub size: u64,
+            #[cfg(feature = "preview")]
+            pub preview: bool,
+        }
+
+        #[tauri_response]
+        pub enum Opened {
+            File(#[cfg(not(feature = "files"))] u32, PathBuf),
+            #[cfg(feature = "preview")]
+            Preview,
+        }
+
+        #[tauri_command_impl]
+        impl TauriCommand<Done> for OpenFile {
+            async fn handle<R: tauri::Runtime>(self, context: CommandContext<R>) -> Result<Done, NoError> {
+                context.state::<AppState>();
+                Ok(Done)
+            }
+        }
+
+        mod inline {
+            use super::*;
+            use tauri::Emitter;
+
+            #[tauri_command]
+            pub struct Ping;
+        }
+        "#,
+    ),
+    ("state.rs", "pub struct AppState;"),
+];
+
+fn client() -> String {
+    let directory = source_directory("client", BACKEND);
+    let cfg = ActiveCfg::with_features(&["files"]);
+    let mut scanner = Scanner::new(&cfg, None);
+    scanner.scan_directory(&directory, "backend");
+    let client = frontend_client(&collect_message_structs(scanner.scan.items()), &cfg);
+    let _ = std::fs::remove_dir_all(directory);
+    client
+}
+
+#[test]
+fn commands_lose_their_handler() {
+    let client = client();
+
+    assert!(client.contains("impl TauriCommand<Done> for OpenFile {}"));
+    assert!(!client.contains("fn handle"));
+    assert!(!client.contains("AppState"));
+    assert!(!client.contains("cfg"));
+}
+
+#[test]
+fn leaves_out_what_the_cfg_does_not_build() {
+    let client = client();
+
+    assert!(client.contains("pub size: u64,"));
+    assert!(!client.contains("preview"));
+    assert!(!client.contains("Preview"));
+    assert!(client.contains("File(PathBuf)"));
+}
+
+#[test]
+fn keeps_the_modules_and_the_imports_they_need() {
+    let client = client();
+
+    assert!(client.contains("pub mod commands {"));
+    assert!(client.contains("pub mod inline {"));
+    assert!(client.contains("use crate::Done;"));
+    assert!(client.contains("use slvr_rust_lib::prelude::*;"));
+    assert!(client.contains("use std::path::PathBuf;"));
+    assert!(client.contains("use super::*;"));
+    // only what the copied items refer to is left of a group
+    assert!(client.contains("use slvr_rust_lib::tauri_response;\n"));
+    // neither the frontend nor the copied items need those
+    assert!(!client.contains("tauri::Manager"));
+    assert!(!client.contains("tauri::Emitter"));
+    assert!(!client.contains("Mutex"));
+    assert!(!client.contains("mod state"));
+    syn::parse_file(&client).expect("the client is valid rust");
+}
@@ -0,0 +1,46 @@
+use crate::scan::{ActiveCfg, Scanner};
+use crate::tauri_wasm::{MessageStructs, collect_message_structs};
+use std::path::{Path, PathBuf};
+
+mod capabilities_test;
+mod client_test;
+mod registry_test;
+mod scan_test;
+mod schema_test;
+mod typescript_test;
+mod validate_test;
+
+/// collects the items of `source` as if it was the file `file` in `src`
+fn collect_source(source: &str, file: &str) -> MessageStructs {
+    let cfg = ActiveCfg::default();
+    let mut scanner = Scanner::new(&cfg, None);
+    let file = Path::new("src").join(file);
+    let module_path = crate::scan::module_path(Path::new("src"), &file);
+
+    scanner.scan_items(
+        syn::parse_file(source)
+            .expect("test source is not valid rust")
+            .items,
+        &module_path,
+        &file,
+        &file.with_extension(""),
+        0,
+    );
+
+    collect_message_structs(scanner.scan.items())
+}
+
+/// writes `files` to a fresh directory in the temp directory
+fn source_directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("slvr_rust_lib_build_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+
+    for (file, source) in files {
+        let path = directory.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+
+    directory
+}
@@ -0,0 +1,242 @@
+use crate::scan::module_path;
+use crate::tauri_wasm::registry::{command_registry, type_string};
+use crate::test::collect_source;
+use std::path::Path;
+
+const COMMANDS: &str = r#"
+    #[tauri_command]
+    pub struct CheckScreenshot {
+        pub path: String,
+    }
+
+    #[tauri_response]
+    pub struct CheckScreenshotResp {
+        pub ok: bool,
+    }
+
+    #[tauri_command_impl]
+    impl TauriCommand<CheckScreenshotResp, ScreenshotError> for CheckScreenshot {
+        const COMMAND_NAME: &'static str = "check-screenshot";
+    }
+
+    #[tauri_command_impl(response = Vec<String>)]
+    impl TauriCommand for ListScreenshots {
+        const COMMAND_NAME: &'static str = "plugin:screenshots|list";
+    }
+
+    #[tauri_command_impl]
+    impl TauriStreamCommand<CheckScreenshotResp> for WatchScreenshots {
+        const COMMAND_NAME: &'static str = "watch_screenshots";
+    }
+
+    impl Display for CheckScreenshot {}
+"#;
+
+#[test]
+fn collects_messages_and_commands() {
+    let message_structs = collect_source(COMMANDS, "commands/screenshot.rs");
+
+    let messages: Vec<String> = message_structs
+        .messages
+        .iter()
+        .map(|message| message.item.ident().to_string())
+        .collect();
+    assert_eq!(messages, ["CheckScreenshot", "CheckScreenshotResp"]);
+
+    let commands: Vec<(String, &str)> = message_structs
+        .commands
+        .iter()
+        .map(|command| {
+            (
+                command.struct_name.to_string(),
+                command.command_name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        commands,
+        [
+            ("CheckScreenshot".to_string(), "check-screenshot"),
+            ("ListScreenshots".to_string(), "plugin:screenshots|list"),
+            ("WatchScreenshots".to_string(), "watch_screenshots")
+        ]
+    );
+    assert!(message_structs.commands[0].error_type.is_some());
+    assert!(message_structs.commands[1].error_type.is_none());
+    assert!(!message_structs.commands[1].streaming);
+    assert!(message_structs.commands[2].streaming);
+}
+
+#[test]
+fn registry_lists_every_command() {
+    let registry = command_registry(&collect_source(COMMANDS, "commands/screenshot.rs"));
+
+    assert!(registry.contains(
+        "\"check-screenshot\" => commands::screenshot::__tauri_invoke_CheckScreenshot!(\
+         commands::screenshot::__tauri_respond_CheckScreenshot, __tauri_invoke__),"
+    ));
+    assert!(registry.contains(
+        "\"plugin:screenshots|list\" => commands::screenshot::__tauri_invoke_ListScreenshots!("
+    ));
+    assert!(registry.contains("slvr_rust_lib::tauri_wasm::cancellation::CANCEL_COMMAND_NAME => {"));
+    assert!(registry.contains("response_type: \"Vec<String>\""));
+    assert!(registry.contains("struct_name: \"crate::commands::screenshot::CheckScreenshot\""));
+}
+
+#[test]
+fn batch_command_dispatches_plain_commands() {
+    let registry = command_registry(&collect_source(COMMANDS, "commands/screenshot.rs"));
+
+    assert!(registry.contains(
+        "slvr_rust_lib::tauri_wasm::batch::BATCH_COMMAND_NAME => {\n                __cmd____slvr_batch!(__slvr_batch, __tauri_invoke__)"
+    ));
+    assert!(registry.contains("async fn __slvr_batch<R: tauri::Runtime>("));
+    assert!(registry.contains("\"check-screenshot\" => {"));
+    assert!(registry.contains("commands::screenshot::__tauri_command_ListScreenshots(\n"));
+    // streaming commands need their channel
+    assert!(!registry.contains("__tauri_command_WatchScreenshots("));
+    // no command lists its windows, so tauri's own capabilities decide
+    assert!(!registry.contains("check_window("));
+}
+
+#[test]
+fn batch_checks_the_windows_of_every_command() {
+    let registry = command_registry(&collect_source(
+        r#"
+        #[tauri_command(windows = ["main", "settings-*"])]
+        pub struct SaveSettings;
+
+        #[tauri_command_impl]
+        impl TauriCommand<()> for SaveSettings {}
+
+        #[tauri_command]
+        pub struct Ping;
+
+        #[tauri_command_impl]
+        impl TauriCommand<()> for Ping {}
+        "#,
+        "commands.rs",
+    ));
+    // prettyplease breaks the calls over several lines
+    let registry: String = registry.split_whitespace().collect();
+
+    assert!(
+        registry.contains(
+            "check_window(\"save_settings\",&[\"main\",\"settings-*\"],window.label(),)?;"
+        )
+    );
+    // the generated capabilities let no window send it
+    assert!(registry.contains("check_window(\"ping\",&[],window.label(),)?;"));
+}
+
+#[test]
+fn root_commands_have_bare_paths() {
+    let registry = command_registry(&collect_source(COMMANDS, "main.rs"));
+
+    assert!(registry.contains(
+        "\"check-screenshot\" => __tauri_invoke_CheckScreenshot!(__tauri_respond_CheckScreenshot, __tauri_invoke__),"
+    ));
+}
+
+#[test]
+fn commands_of_other_crates_are_only_listed() {
+    let mut message_structs = collect_source(COMMANDS, "commands/screenshot.rs");
+    for command in &mut message_structs.commands {
+        command.module_path = String::from("app_messages::screenshot");
+    }
+    let registry = command_registry(&message_structs);
+
+    assert!(registry.contains("struct_name: \"app_messages::screenshot::CheckScreenshot\""));
+    assert!(!registry.contains("__tauri_invoke_CheckScreenshot!("));
+    assert!(!registry.contains("__tauri_command_ListScreenshots("));
+}
+
+#[test]
+fn module_paths() {
+    let src = Path::new("src");
+    assert_eq!(module_path(src, &src.join("main.rs")), "crate");
+    assert_eq!(module_path(src, &src.join("lib.rs")), "crate");
+    assert_eq!(
+        module_path(src, &src.join("commands/mod.rs")),
+        "crate::commands"
+    );
+    assert_eq!(
+        module_path(src, &src.join("commands/files.rs")),
+        "crate::commands::files"
+    );
+    assert_eq!(
+        module_path(src, &src.join("commands/main.rs")),
+        "crate::commands::main"
+    );
+}
+
+#[test]
+fn default_command_names() {
+    let message_structs = collect_source(
+        r#"
+        #[tauri_command]
+        pub struct TakeScreenshot;
+
+        #[tauri_command(name = "plugin:screenshots|delete")]
+        pub struct DeleteScreenshot {
+            pub path: String,
+        }
+
+        #[tauri_command_impl(response = String)]
+        impl TauriCommand for TakeScreenshot {}
+
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for DeleteScreenshot {}
+        "#,
+        "commands/screenshot.rs",
+    );
+
+    let commands: Vec<&str> = message_structs
+        .commands
+        .iter()
+        .map(|command| command.command_name.as_str())
+        .collect();
+    assert_eq!(commands, ["take_screenshot", "plugin:screenshots|delete"]);
+    assert!(!message_structs.commands[0].explicit_name);
+}
+
+#[test]
+fn generic_command_instances() {
+    let message_structs = collect_source(
+        r#"
+        #[tauri_command]
+        pub struct ListPage<T> {
+            pub page: u32,
+            pub filter: Option<T>,
+        }
+
+        #[tauri_command_impl(instances(ListPage<File> = "list_file_page", ListPage<Dir> = "list_dir_page"))]
+        impl<T: MsgSafe> TauriCommand<Page<T>> for ListPage<T> {}
+        "#,
+        "pages.rs",
+    );
+
+    let commands: Vec<(&str, String)> = message_structs
+        .commands
+        .iter()
+        .map(|command| {
+            (
+                command.command_name.as_str(),
+                type_string(&command.response_type),
+            )
+        })
+        .collect();
+    assert_eq!(
+        commands,
+        [
+            ("list_file_page", "Page<File>".to_string()),
+            ("list_dir_page", "Page<Dir>".to_string())
+        ]
+    );
+
+    let registry = command_registry(&message_structs);
+    assert!(registry.contains("struct_name: \"crate::pages::ListPage<Dir>\""));
+    assert!(registry.contains(
+        "\"list_dir_page\" => pages::__tauri_invoke_ListPage_1!(pages::__tauri_respond_ListPage_1, __tauri_invoke__),"
+    ));
+}
@@ -0,0 +1,391 @@
+use crate::scan::{
+    ActiveCfg, ItemKind, Scan, ScanCache, ScannedItem, Scanner, Workspace, WorkspaceMember,
+};
+use crate::tauri_wasm::workspace_scan;
+use crate::test::source_directory;
+use std::path::{Path, PathBuf};
+
+/// the full path of every item with `attribute`
+fn item_paths(scan: &Scan, attribute: &str) -> Vec<String> {
+    scan.items_with_attribute(attribute)
+        .map(|item| format!("{}::{}", item.module_path, item.ident))
+        .collect()
+}
+
+fn scan_directory(directory: &Path, cfg: &ActiveCfg, cache: Option<ScanCache>) -> Scan {
+    let mut scanner = Scanner::new(cfg, cache);
+    scanner.scan_directory(directory, "app");
+    scanner.scan
+}
+
+const CRATE: &[(&str, &str)] = &[
+    (
+        "lib.rs",
+        r#"
+        mod commands;
+        #[cfg(feature = "extra-commands")]
+        mod extra;
+        #[path = "generated/messages.rs"]
+        mod generated;
+
+        mod inline {
+            #[tauri_command]
+            pub struct Inline;
+
+            mod nested;
+        }
+
+        #[cfg(not(feature = "extra-commands"))]
+        #[tauri_command]
+        pub struct Fallback;
+        "#,
+    ),
+    ("commands/mod.rs", "mod files;"),
+    (
+        "commands/files.rs",
+        "#[tauri_command] pub struct ReadFile; #[cfg(test)] #[tauri_command] pub struct TestOnly;",
+    ),
+    ("extra.rs", "#[tauri_command] pub struct Extra;"),
+    (
+        "generated/messages.rs",
+        "#[tauri_response] pub struct Generated;",
+    ),
+    ("inline/nested.rs", "#[tauri_response] pub struct Nested;"),
+    ("unused.rs", "#[tauri_command] pub struct Unused;"),
+];
+
+#[test]
+fn follows_mod_declarations() {
+    let directory = source_directory("modules", CRATE);
+    let scan = scan_directory(&directory, &ActiveCfg::default(), None);
+
+    let paths: Vec<String> = scan
+        .items()
+        .iter()
+        .map(|item| format!("{}::{}", item.module_path, item.ident))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "crate::commands::files::ReadFile",
+            "crate::generated::Generated",
+            "crate::inline::Inline",
+            "crate::inline::nested::Nested",
+            "crate::Fallback",
+        ]
+    );
+    let files: Vec<&Path> = scan
+        .files()
+        .iter()
+        .map(|file| file.strip_prefix(&directory).unwrap())
+        .collect();
+    assert_eq!(
+        files,
+        [
+            Path::new("lib.rs"),
+            Path::new("commands/mod.rs"),
+            Path::new("commands/files.rs"),
+            Path::new("generated/messages.rs"),
+            Path::new("inline/nested.rs"),
+        ]
+    );
+    assert!(scan.warnings().is_empty());
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn respects_features() {
+    let directory = source_directory("features", CRATE);
+    let scan = scan_directory(
+        &directory,
+        &ActiveCfg::with_features(&["extra-commands"]),
+        None,
+    );
+
+    let paths = item_paths(&scan, "tauri_command");
+    assert!(paths.contains(&String::from("crate::extra::Extra")));
+    assert!(!paths.contains(&String::from("crate::Fallback")));
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn reports_problems_with_locations() {
+    let directory = source_directory(
+        "warnings",
+        &[
+            ("lib.rs", "mod broken;\nmod missing;\n"),
+            ("broken.rs", "pub struct Broken {\n    field: ,\n}\n"),
+        ],
+    );
+    let scan = scan_directory(&directory, &ActiveCfg::default(), None);
+
+    assert_eq!(scan.warnings().len(), 2);
+    assert!(
+        scan.warnings()[0].starts_with(&format!(
+            "{}:2:12: could not parse file:",
+            directory.join("broken.rs").display()
+        )),
+        "{}",
+        scan.warnings()[0]
+    );
+    assert_eq!(
+        scan.warnings()[1],
+        format!(
+            "{}:2:5: could not find the file of module `missing`",
+            directory.join("lib.rs").display()
+        )
+    );
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+const ITEMS: &str = r#"
+#[tauri_command]
+pub struct ReadFile;
+
+/// not an event
+#[derive(Debug)]
+pub enum Kind {}
+
+#[tauri_event]
+pub enum FileEvent {}
+
+#[tauri_command_impl]
+impl TauriCommand<String> for ReadFile {}
+
+#[tauri_event]
+pub fn on_file() {}
+
+impl Kind {}
+"#;
+
+#[test]
+fn items_with_attribute() {
+    let directory = source_directory("attributes", &[("lib.rs", ITEMS)]);
+    let scan = scan_directory(&directory, &ActiveCfg::default(), None);
+
+    let kinds: Vec<ItemKind> = scan.items().iter().map(|item| item.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ItemKind::Struct,
+            ItemKind::Enum,
+            ItemKind::Enum,
+            ItemKind::Impl,
+            ItemKind::Fn
+        ]
+    );
+
+    let events: Vec<&ScannedItem> = scan.items_with_attribute("tauri_event").collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].ident, "FileEvent");
+    assert_eq!(
+        events[1].location(),
+        format!("{}:16:8", directory.join("lib.rs").display())
+    );
+    assert_eq!(item_paths(&scan, "tauri_command_impl"), ["crate::ReadFile"]);
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn unchanged_files_come_from_the_cache() {
+    let directory = source_directory("cache", &[("lib.rs", "mod files;\n"), ("files.rs", ITEMS)]);
+    let cache_directory = directory.join("out");
+    let scan = || {
+        scan_directory(
+            &directory,
+            &ActiveCfg::default(),
+            Some(ScanCache::new(cache_directory.clone())),
+        )
+    };
+
+    let first = scan();
+    // one cache file for every source file
+    let cache_files: Vec<PathBuf> = std::fs::read_dir(&cache_directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(cache_files.len(), 2);
+    let cached = scan();
+
+    let summary = |scan: &Scan| -> Vec<String> {
+        scan.items()
+            .iter()
+            .map(|item| format!("{}::{} {}", item.module_path, item.ident, item.location()))
+            .collect()
+    };
+    assert_eq!(summary(&first), summary(&cached));
+
+    std::fs::write(
+        directory.join("files.rs"),
+        "#[tauri_event]\npub fn changed() {}\n",
+    )
+    .unwrap();
+    assert_eq!(
+        item_paths(&scan(), "tauri_event"),
+        ["crate::files::changed"]
+    );
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+const WORKSPACE: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        r#"
+        [workspace]
+        members = ["crates/*", "frontend"]
+        exclude = ["crates/old_*"]
+        "#,
+    ),
+    (
+        "crates/app_messages/Cargo.toml",
+        "[package]\nname = \"app_messages\"",
+    ),
+    (
+        "crates/app_messages/src/lib.rs",
+        "pub mod files; #[tauri_message] pub struct Notice;",
+    ),
+    (
+        "crates/app_messages/src/files.rs",
+        "#[tauri_command] pub struct ListFiles;",
+    ),
+    (
+        "crates/old_messages/Cargo.toml",
+        "[package]\nname = \"old_messages\"",
+    ),
+    (
+        "frontend/Cargo.toml",
+        "[package]\nname = \"app-frontend\"\n[lib]\npath = \"source/lib.rs\"",
+    ),
+    (
+        "frontend/source/lib.rs",
+        "#[tauri_response] pub struct Shown;",
+    ),
+];
+
+#[test]
+fn discovers_workspace_members() {
+    let directory = source_directory("workspace_members", WORKSPACE);
+    // found from any member
+    let workspace = Workspace::from_manifest_dir(directory.join("frontend")).unwrap();
+
+    assert_eq!(workspace.root(), directory);
+    assert_eq!(
+        workspace.members(),
+        [
+            WorkspaceMember {
+                name: String::from("app_messages"),
+                manifest_dir: directory.join("crates/app_messages"),
+                root_file: directory.join("crates/app_messages/src/lib.rs"),
+                source_directory: directory.join("crates/app_messages/src"),
+            },
+            WorkspaceMember {
+                name: String::from("app-frontend"),
+                manifest_dir: directory.join("frontend"),
+                root_file: directory.join("frontend/source/lib.rs"),
+                source_directory: directory.join("frontend/source"),
+            },
+        ]
+    );
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn scans_workspace_crates() {
+    let directory = source_directory("workspace_scan", WORKSPACE);
+    let workspace = Workspace::from_manifest_dir(&directory).unwrap();
+    let scan = workspace.scan(&["app_messages", "app-frontend", "old_messages"]);
+
+    let paths: Vec<(&str, String)> = scan
+        .items()
+        .iter()
+        .map(|item| (item.crate_name.as_str(), item.path()))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            (
+                "app_messages",
+                String::from("app_messages::files::ListFiles")
+            ),
+            ("app_messages", String::from("app_messages::Notice")),
+            ("app-frontend", String::from("app_frontend::Shown")),
+        ]
+    );
+    assert!(
+        scan.files()
+            .contains(&directory.join("crates/app_messages/Cargo.toml"))
+    );
+    assert_eq!(scan.warnings().len(), 1);
+    assert!(scan.warnings()[0].ends_with("`old_messages` is not a member of the workspace"));
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn reads_members_from_their_crate_root() {
+    let directory = source_directory(
+        "workspace_roots",
+        &[
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"tools\", \"server\", \"app\"]",
+            ),
+            (
+                "tools/Cargo.toml",
+                "[package]\nname = \"tools\"\n[lib]\npath = \"src/tools.rs\"",
+            ),
+            (
+                "tools/src/tools.rs",
+                "mod shared; #[tauri_message] pub struct Tool;",
+            ),
+            ("tools/src/shared.rs", "#[tauri_message] pub struct Shared;"),
+            ("tools/src/unused.rs", "#[tauri_message] pub struct Unused;"),
+            (
+                "server/Cargo.toml",
+                "[package]\nname = \"server\"\n[[bin]]\nname = \"server\"\npath = \"bin/server.rs\"",
+            ),
+            ("server/bin/server.rs", "#[tauri_command] pub struct Start;"),
+            ("app/Cargo.toml", "[package]\nname = \"app\""),
+            ("app/src/lib.rs", "#[tauri_message] pub struct Library;"),
+            ("app/src/main.rs", "#[tauri_message] pub struct Binary;"),
+        ],
+    );
+    let workspace = Workspace::from_manifest_dir(&directory).unwrap();
+    assert_eq!(
+        workspace.member("server").unwrap().root_file,
+        directory.join("server/bin/server.rs")
+    );
+
+    let scan = workspace.scan(&["tools", "server", "app"]);
+    let paths: Vec<String> = scan.items().iter().map(ScannedItem::path).collect();
+    // files the root does not declare and the binary next to a library are not read
+    assert_eq!(
+        paths,
+        [
+            "tools::shared::Shared",
+            "tools::Tool",
+            "server::Start",
+            "app::Library"
+        ]
+    );
+    assert!(scan.warnings().is_empty());
+    let _ = std::fs::remove_dir_all(directory);
+}
+
+#[test]
+fn unreadable_workspaces_fall_back_to_the_crate() {
+    let directory = source_directory(
+        "workspace_broken",
+        &[
+            ("Cargo.toml", "[workspace\nmembers = [\"crates/*\"]\n"),
+            ("src/lib.rs", "#[tauri_command]\npub struct Ping;\n"),
+        ],
+    );
+    let workspace = Workspace::from_manifest_dir(&directory);
+    assert!(workspace.is_err());
+
+    let scan = workspace_scan(workspace, &["app_messages"], directory.join("src"));
+    assert_eq!(item_paths(&scan, "tauri_command"), ["crate::Ping"]);
+    assert_eq!(scan.warnings().len(), 1);
+    assert!(scan.warnings()[0].starts_with(&directory.join("Cargo.toml").display().to_string()));
+    let _ = std::fs::remove_dir_all(directory);
+}
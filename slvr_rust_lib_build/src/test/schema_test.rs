@@ -0,0 +1,101 @@
+use crate::tauri_wasm::registry::command_registry;
+use crate::tauri_wasm::schema::command_fingerprint;
+use crate::tauri_wasm::{MessageStructs, schema_fingerprint};
+use crate::test::collect_source;
+
+const MESSAGES: &str = r#"
+    #[tauri_command]
+    pub struct ReadFile {
+        pub path: String,
+    }
+
+    #[tauri_response]
+    pub struct FileContents {
+        pub text: String,
+        pub metadata: Metadata,
+    }
+
+    #[tauri_message]
+    pub struct Metadata {
+        pub size: u64,
+    }
+
+    #[tauri_command]
+    pub struct ListFiles;
+
+    #[tauri_command_impl]
+    impl TauriCommand<FileContents> for ReadFile {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Vec<String>> for ListFiles {}
+"#;
+
+/// the fingerprint of every command, by command name
+fn command_fingerprints(message_structs: &MessageStructs) -> Vec<(String, String)> {
+    message_structs
+        .commands
+        .iter()
+        .map(|command| {
+            (
+                command.command_name.clone(),
+                command_fingerprint(message_structs, command),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn doc_comments_and_modules_keep_the_fingerprint() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+    let documented = MESSAGES.replace(
+        "pub struct Metadata {",
+        "/// the metadata of a file\n    pub struct Metadata {\n        /// in bytes",
+    );
+    let moved = collect_source(&documented, "files/messages.rs");
+
+    assert_eq!(
+        schema_fingerprint(&message_structs),
+        schema_fingerprint(&moved)
+    );
+    assert_eq!(
+        command_fingerprints(&message_structs),
+        command_fingerprints(&moved)
+    );
+}
+
+#[test]
+fn changed_messages_change_the_commands_using_them() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+    let changed = collect_source(
+        &MESSAGES.replace("pub size: u64", "pub size: u32"),
+        "messages.rs",
+    );
+
+    assert_ne!(
+        schema_fingerprint(&message_structs),
+        schema_fingerprint(&changed)
+    );
+
+    let before = command_fingerprints(&message_structs);
+    let after = command_fingerprints(&changed);
+    // `ReadFile` reaches `Metadata` through the fields of its response
+    assert_eq!(before[0].0, "read_file");
+    assert_ne!(before[0].1, after[0].1);
+    assert_eq!(before[1].0, "list_files");
+    assert_eq!(before[1].1, after[1].1);
+}
+
+#[test]
+fn registry_answers_the_handshake() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+    let registry = command_registry(&message_structs);
+
+    assert!(registry.contains(&format!(
+        "fingerprint: \"{}\"",
+        schema_fingerprint(&message_structs)
+    )));
+    assert!(registry.contains("command_name: \"list_files\""));
+    assert!(registry.contains(
+        "slvr_rust_lib::tauri_wasm::schema::handshake_invoke(__tauri_invoke__, &TAURI_SCHEMA)"
+    ));
+}
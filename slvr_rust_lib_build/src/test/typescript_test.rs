@@ -0,0 +1,214 @@
+use crate::tauri_wasm::typescript::{javascript_bindings, typescript_declarations};
+use crate::test::collect_source;
+
+const MESSAGES: &str = r#"
+    #[tauri_command]
+    #[serde(rename_all = "camelCase")]
+    pub struct ReadFile {
+        pub file_path: String,
+        #[serde(rename = "len")]
+        pub max_length: Option<u64>,
+        #[serde(skip)]
+        pub cache: Vec<u8>,
+        #[serde(flatten)]
+        pub options: ReadOptions,
+    }
+
+    #[tauri_response]
+    pub struct Page<T> {
+        pub items: Vec<T>,
+        #[serde(default)]
+        pub next: u32,
+    }
+
+    #[tauri_response]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum FileOp {
+        Read { path: String },
+        Write(WriteOp),
+        #[serde(skip)]
+        Internal,
+        Delete,
+    }
+
+    #[tauri_response]
+    pub enum Direction {
+        Up,
+        Down,
+    }
+
+    #[tauri_response]
+    #[serde(tag = "t", content = "c")]
+    pub enum Adjacent {
+        Count(u32),
+        Empty,
+    }
+
+    #[tauri_response]
+    pub struct FileId(pub String);
+
+    #[tauri_message]
+    pub struct ReadOptions {
+        pub follow_links: bool,
+    }
+
+    #[tauri_message]
+    pub struct WriteOp {
+        pub path: String,
+    }
+
+    #[tauri_response]
+    pub enum FileError {
+        NotFound,
+    }
+
+    #[tauri_command]
+    pub struct ListPage<T> {
+        pub filter: Option<T>,
+    }
+
+    #[tauri_command]
+    pub struct ListFiles;
+
+    #[tauri_response]
+    pub struct Stat {
+        pub modified: OffsetDateTime,
+        pub day: Date,
+        pub created: DateTime<Utc>,
+        pub age: Duration,
+        pub pages: Page<Stat>,
+    }
+
+    #[tauri_response]
+    #[serde(rename = "file_entry")]
+    pub struct FileEntry {
+        pub id: FileId,
+    }
+
+    #[tauri_response]
+    pub struct Listing {
+        pub entries: Vec<FileEntry>,
+    }
+
+    #[tauri_command]
+    pub struct GetEntry;
+
+    #[tauri_command_impl]
+    impl TauriCommand<FileEntry> for GetEntry {}
+
+    #[tauri_command_impl]
+    impl TauriCommand<Page<FileId>, FileError> for ReadFile {
+        const COMMAND_NAME: &'static str = "read_file";
+    }
+
+    #[tauri_command_impl(instances(ListPage<FileId> = "list_file_ids"))]
+    impl<T: MsgSafe> TauriCommand<Page<T>> for ListPage<T> {}
+
+    #[tauri_command(binary, name = "upload_image")]
+    pub struct UploadImage {
+        pub bytes: Vec<u8>,
+    }
+
+    #[tauri_response(binary)]
+    pub struct Thumbnail {
+        pub bytes: Vec<u8>,
+    }
+
+    #[tauri_command_impl]
+    impl TauriCommand<Thumbnail> for UploadImage {}
+
+    #[tauri_command_impl]
+    impl TauriStreamCommand<FileId> for ListFiles {
+        const COMMAND_NAME: &'static str = "list_files";
+    }
+"#;
+
+#[test]
+fn struct_declarations() {
+    let declarations = typescript_declarations(&collect_source(MESSAGES, "messages.rs"));
+
+    assert!(declarations.contains(
+        "export type ReadFile = {\n    filePath: string;\n    len?: number | null;\n} & ReadOptions;\n"
+    ));
+    assert!(
+        declarations
+            .contains("export interface Page<T> {\n    items: T[];\n    next?: number;\n}\n")
+    );
+    assert!(declarations.contains("export type FileId = string;\n"));
+}
+
+#[test]
+fn types_without_a_declaration_are_unknown() {
+    let declarations = typescript_declarations(&collect_source(MESSAGES, "messages.rs"));
+
+    assert!(declarations.contains(
+        "export interface Stat {\n    \
+         modified: [number, number, number, number, number, number, number, number, number];\n    \
+         day: [number, number];\n    created: string;\n    age: unknown;\n    pages: Page<Stat>;\n}\n"
+    ));
+}
+
+#[test]
+fn renamed_messages_keep_their_rust_name() {
+    let declarations = typescript_declarations(&collect_source(MESSAGES, "messages.rs"));
+
+    assert!(declarations.contains("export interface FileEntry {\n    id: FileId;\n}\n"));
+    assert!(declarations.contains("export interface Listing {\n    entries: FileEntry[];\n}\n"));
+    assert!(
+        declarations
+            .contains("export declare function getEntry(args: GetEntry): Promise<FileEntry>;\n")
+    );
+    assert!(!declarations.contains("file_entry"));
+}
+
+#[test]
+fn enum_declarations() {
+    let declarations = typescript_declarations(&collect_source(MESSAGES, "messages.rs"));
+
+    assert!(declarations.contains(
+        "export type FileOp =\n    | { type: \"read\" } & { path: string }\n    | { type: \"write\" } & WriteOp\n    | { type: \"delete\" };\n"
+    ));
+    assert!(declarations.contains("export type Direction =\n    | \"Up\"\n    | \"Down\";\n"));
+    assert!(declarations.contains(
+        "export type Adjacent =\n    | { t: \"Count\"; c: number }\n    | { t: \"Empty\" };\n"
+    ));
+}
+
+#[test]
+fn command_wrappers() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+
+    assert!(
+        typescript_declarations(&message_structs)
+            .contains("export declare function readFile(args: ReadFile): Promise<Page<FileId>>;\n")
+    );
+    assert!(typescript_declarations(&message_structs).contains(
+        "export declare function listFileIds(args: ListPage<FileId>): Promise<Page<FileId>>;\n"
+    ));
+    assert!(javascript_bindings(&message_structs).contains(
+        "export function readFile(args) {\n    return window.__TAURI__.core.invoke(\"read_file\", { args });\n}\n"
+    ));
+}
+
+#[test]
+fn binary_command_wrappers() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+
+    assert!(typescript_declarations(&message_structs).contains(
+        "export declare function uploadImage(args: UploadImage): Promise<ArrayBuffer>;\n"
+    ));
+}
+
+#[test]
+fn stream_command_wrappers() {
+    let message_structs = collect_source(MESSAGES, "messages.rs");
+
+    assert!(typescript_declarations(&message_structs).contains(
+        "export declare function listFiles(args: ListFiles, onItem: (item: FileId) => void): Promise<void>;\n"
+    ));
+    assert!(
+        javascript_bindings(&message_structs).contains(
+            "    return window.__TAURI__.core.invoke(\"list_files\", { args, channel });\n"
+        )
+    );
+}
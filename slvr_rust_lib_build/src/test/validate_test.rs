@@ -0,0 +1,195 @@
+use crate::tauri_wasm::{BUILTIN_COMMANDS, ConflictKind, NameConflict, validate_message_structs};
+use crate::test::collect_source;
+use slvr_rust_lib_common::tauri_wasm::{batch, cancellation, schema};
+
+const CONFLICTS: &str = r#"
+#[tauri_command]
+pub struct Ping;
+
+#[tauri_command_impl(response = bool)]
+impl TauriCommand for Ping {}
+
+#[tauri_event]
+pub struct Progress;
+
+mod other {
+    #[tauri_command(name = "ping")]
+    pub struct OtherPing;
+
+    #[tauri_command_impl(response = bool)]
+    impl TauriCommand for OtherPing {}
+
+    #[tauri_event(name = "progress")]
+    pub struct OtherProgress;
+
+    #[tauri_response]
+    pub struct Ping;
+}
+"#;
+
+#[test]
+fn reports_every_conflict_with_its_locations() {
+    let conflicts = validate_message_structs(&collect_source(CONFLICTS, "lib.rs")).unwrap_err();
+
+    assert_eq!(
+        conflicts,
+        [
+            NameConflict {
+                kind: ConflictKind::CommandName,
+                name: String::from("ping"),
+                locations: vec![
+                    String::from("src/lib.rs:6:23"),
+                    String::from("src/lib.rs:16:27")
+                ],
+            },
+            NameConflict {
+                kind: ConflictKind::EventName,
+                name: String::from("progress"),
+                locations: vec![
+                    String::from("src/lib.rs:9:12"),
+                    String::from("src/lib.rs:19:16")
+                ],
+            },
+            NameConflict {
+                kind: ConflictKind::StructName,
+                name: String::from("Ping"),
+                locations: vec![
+                    String::from("src/lib.rs:3:12"),
+                    String::from("src/lib.rs:22:16")
+                ],
+            },
+        ]
+    );
+    assert_eq!(
+        conflicts[0].to_string(),
+        "the command name `ping` is used by more than one command\n  --> src/lib.rs:6:23\n  --> src/lib.rs:16:27"
+    );
+}
+
+#[test]
+fn generic_instances_and_distinct_names_pass() {
+    let message_structs = collect_source(
+        r#"
+        #[tauri_command]
+        pub struct ListPage<T> {
+            pub filter: Option<T>,
+        }
+
+        #[tauri_command_impl(instances(ListPage<File> = "list_files", ListPage<Dir> = "list_dirs"))]
+        impl<T> TauriCommand<Page<T>> for ListPage<T> {}
+
+        #[tauri_event]
+        pub struct Progress;
+
+        #[tauri_event(name = "done")]
+        pub struct Finished;
+        "#,
+        "lib.rs",
+    );
+
+    assert_eq!(validate_message_structs(&message_structs), Ok(()));
+}
+
+#[test]
+fn instances_sharing_a_name_and_builtin_names_conflict() {
+    let conflicts = validate_message_structs(&collect_source(
+        r#"
+        #[tauri_command_impl(instances(ListPage<File> = "list", ListPage<Dir> = "list"))]
+        impl<T> TauriCommand<Page<T>> for ListPage<T> {}
+
+        #[tauri_command(name = "__slvr_batch")]
+        pub struct Batch;
+
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for Batch {}
+
+        #[tauri_event]
+        pub struct r#Loop;
+
+        #[tauri_event(name = "loop")]
+        pub struct Repeat;
+        "#,
+        "lib.rs",
+    ))
+    .unwrap_err();
+
+    assert_eq!(
+        conflicts,
+        [
+            NameConflict {
+                kind: ConflictKind::CommandName,
+                name: String::from("list"),
+                locations: vec![
+                    String::from("src/lib.rs:3:43"),
+                    String::from("src/lib.rs:3:43")
+                ],
+            },
+            NameConflict {
+                kind: ConflictKind::BuiltinCommandName,
+                name: String::from("__slvr_batch"),
+                locations: vec![String::from("src/lib.rs:9:31")],
+            },
+            NameConflict {
+                kind: ConflictKind::EventName,
+                name: String::from("loop"),
+                locations: vec![
+                    String::from("src/lib.rs:12:20"),
+                    String::from("src/lib.rs:15:20")
+                ],
+            },
+        ]
+    );
+}
+
+#[test]
+fn typescript_function_names_must_be_unique_and_valid() {
+    let conflicts = validate_message_structs(&collect_source(
+        r#"
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for Dashed {
+            const COMMAND_NAME: &'static str = "read-file";
+        }
+
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for Underscored {
+            const COMMAND_NAME: &'static str = "read_file";
+        }
+
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for First {
+            const COMMAND_NAME: &'static str = "1st";
+        }
+
+        #[tauri_command_impl(response = bool)]
+        impl TauriCommand for Delete {}
+        "#,
+        "lib.rs",
+    ))
+    .unwrap_err();
+
+    let conflicts: Vec<(ConflictKind, &str)> = conflicts
+        .iter()
+        .map(|conflict| (conflict.kind, conflict.name.as_str()))
+        .collect();
+    assert_eq!(
+        conflicts,
+        [
+            (ConflictKind::FunctionName, "readFile"),
+            (ConflictKind::InvalidFunctionName, "1st"),
+            (ConflictKind::InvalidFunctionName, "delete"),
+        ]
+    );
+}
+
+#[test]
+fn builtin_command_names_are_the_ones_of_common() {
+    // copied so the build crate does not depend on slvr_rust_lib_common
+    assert_eq!(
+        BUILTIN_COMMANDS,
+        [
+            batch::BATCH_COMMAND_NAME,
+            cancellation::CANCEL_COMMAND_NAME,
+            schema::HANDSHAKE_COMMAND_NAME,
+        ]
+    );
+}
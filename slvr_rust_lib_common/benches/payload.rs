@@ -0,0 +1,112 @@
+//! Compares json and postcard on the backend's side of a message carrying a file, see
+//! `tauri_wasm::payload::Payload`: encoding it into the body `Payload::into_response` hands to
+//! tauri, and decoding it from the body tauri hands to the generated command.
+//!
+//! Only the backend is measured. The frontend needs a wasm runtime, there json messages are
+//! converted to and from `JsValue`s with `serde_wasm_bindgen` and binary ones are copied between
+//! the js heap and wasm memory, none of which shows up here.
+//!
+//! `cargo bench -p slvr_rust_lib_common --features tauri_wasm`
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use serde::{Deserialize, Serialize};
+use slvr_rust_lib_common::tauri_wasm::MsgSafe;
+use slvr_rust_lib_common::tauri_wasm::payload::Payload;
+use std::hint::black_box;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JsonFile {
+    path: String,
+    contents: Vec<u8>,
+}
+
+impl MsgSafe for JsonFile {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BinaryFile {
+    path: String,
+    contents: Vec<u8>,
+}
+
+impl MsgSafe for BinaryFile {
+    const BINARY: bool = true;
+}
+
+const SIZES: [usize; 3] = [1 << 10, 1 << 16, 1 << 20];
+
+fn contents(size: usize) -> Vec<u8> {
+    (0..size).map(|index| index as u8).collect()
+}
+
+/// the body of the response, like `Payload::into_response`
+fn to_wire(payload: Payload) -> Vec<u8> {
+    match payload {
+        Payload::Json(value) => value.to_string().into_bytes(),
+        Payload::Binary(bytes) => bytes,
+    }
+}
+
+fn encode<T: MsgSafe>(message: &T) -> Vec<u8> {
+    to_wire(Payload::encode(message).unwrap())
+}
+
+/// tauri parses json bodies into a `Value` before the generated command reads it
+fn decode<T: MsgSafe>(bytes: &[u8]) -> T {
+    let payload = if T::BINARY {
+        Payload::Binary(bytes.to_vec())
+    } else {
+        Payload::Json(serde_json::from_slice(bytes).unwrap())
+    };
+    payload.decode().unwrap()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for size in SIZES {
+        let path = String::from("screenshots/latest.png");
+        let json = JsonFile {
+            path: path.clone(),
+            contents: contents(size),
+        };
+        let binary = BinaryFile {
+            path,
+            contents: contents(size),
+        };
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("json", size), &json, |b, file| {
+            b.iter(|| encode(black_box(file)))
+        });
+        group.bench_with_input(BenchmarkId::new("binary", size), &binary, |b, file| {
+            b.iter(|| encode(black_box(file)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for size in SIZES {
+        let path = String::from("screenshots/latest.png");
+        let json = encode(&JsonFile {
+            path: path.clone(),
+            contents: contents(size),
+        });
+        let binary = encode(&BinaryFile {
+            path,
+            contents: contents(size),
+        });
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("json", size), &json, |b, bytes| {
+            b.iter(|| decode::<JsonFile>(black_box(bytes)))
+        });
+        group.bench_with_input(BenchmarkId::new("binary", size), &binary, |b, bytes| {
+            b.iter(|| decode::<BinaryFile>(black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
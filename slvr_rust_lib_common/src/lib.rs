@@ -5,3 +5,6 @@ pub mod tauri_wasm;
 #[cfg(feature = "encapsulation")]
 pub mod encapsulation;
 mod error;
+
+#[cfg(all(test, feature = "tauri_wasm_mock"))]
+mod test;
@@ -0,0 +1,270 @@
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::ConversionError;
+use crate::tauri_wasm::MsgSafe;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::command::{CommandError, NoError, TauriCommand, TauriResult};
+#[cfg(feature = "tauri_wasm_backend")]
+use crate::tauri_wasm::middleware::CommandResult;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::payload::Payload;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::transport::current_transport;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::try_to_json;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::marker::PhantomData;
+
+/// The command a `Batch` is sent as, handled by `tauri_commands!()` and `HttpRouter`.
+pub const BATCH_COMMAND_NAME: &str = "__slvr_batch";
+
+/// How the backend handles the commands of a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// All commands are handled at the same time.
+    #[default]
+    Concurrent,
+    /// Every command is handled once the one before it finished.
+    InOrder,
+}
+
+/// A batch as it is sent to the backend.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub mode: BatchMode,
+    pub commands: Vec<BatchCommand>,
+}
+
+impl MsgSafe for BatchRequest {}
+
+/// A command of a batch, `args` is the serialized command.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchCommand {
+    pub command: String,
+    pub args: Value,
+}
+
+/// Sends many `TauriCommand`s to the backend in one round trip:
+/// ```ignore
+/// let mut batch = Batch::new();
+/// let file = batch.add(ReadFile { path });
+/// let screenshots = batch.add(ListScreenshots);
+///
+/// let results = batch.send().await?;
+/// let file: Result<FileContents, CommandError<FileError>> = results.get(file);
+/// ```
+/// Every command is handled by the same handler and middlewares as when it is sent on its own.
+/// Streaming commands cannot be batched, binary commands and responses are sent as json arrays
+/// inside the batch, so large buffers are better sent on their own.
+#[cfg(feature = "tauri_wasm_frontend")]
+#[derive(Default)]
+pub struct Batch {
+    mode: BatchMode,
+    commands: Vec<(&'static str, Result<Value, ConversionError>)>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl Batch {
+    pub fn new() -> Self {
+        Batch::default()
+    }
+
+    /// Handles the commands one after another, in the order they were added.
+    pub fn in_order(mut self) -> Self {
+        self.mode = BatchMode::InOrder;
+        self
+    }
+
+    /// Adds `command` to the batch, its result is read from `BatchResults` with the returned item.
+    pub fn add<C, Response, Error>(&mut self, command: C) -> BatchItem<Response, Error>
+    where
+        C: TauriCommand<Response, Error>,
+        Response: TauriResult,
+        Error: MsgSafe,
+    {
+        self.commands.push((C::COMMAND_NAME, try_to_json(&command)));
+        BatchItem {
+            index: self.commands.len() - 1,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Sends every command that could be converted and waits for all of them to be handled.
+    ///
+    /// Fails if the batch itself could not be sent, the results of the commands are read with `BatchResults::get`.
+    pub async fn send(self) -> Result<BatchResults, CommandError<NoError>> {
+        let request = BatchRequest {
+            mode: self.mode,
+            commands: self
+                .commands
+                .iter()
+                .filter_map(|(command, args)| {
+                    Some(BatchCommand {
+                        command: command.to_string(),
+                        args: args.as_ref().ok()?.clone(),
+                    })
+                })
+                .collect(),
+        };
+
+        let mut sent_results = if request.commands.is_empty() {
+            Vec::new()
+        } else {
+            let args = Payload::encode(&request)?;
+            match current_transport().invoke(BATCH_COMMAND_NAME, args).await {
+                Ok(payload) => payload.decode::<Vec<Result<Payload, Value>>>()?,
+                Err(rejection) => return Err(CommandError::from_rejection(rejection)),
+            }
+        }
+        .into_iter();
+
+        // the commands that could not be converted were not sent, so they have no result to skip
+        let results = self
+            .commands
+            .into_iter()
+            .map(|(_, args)| match args {
+                Ok(_) => sent_results.next().map(Ok),
+                Err(err) => Some(Err(err)),
+            })
+            .collect();
+
+        Ok(BatchResults { results })
+    }
+}
+
+/// A command added to a `Batch`, reads its result from the `BatchResults`.
+#[cfg(feature = "tauri_wasm_frontend")]
+pub struct BatchItem<Response, Error> {
+    index: usize,
+    _marker: PhantomData<fn() -> (Response, Error)>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<Response, Error> Clone for BatchItem<Response, Error> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<Response, Error> Copy for BatchItem<Response, Error> {}
+
+/// The results of a `Batch`, in the order the commands were added.
+#[cfg(feature = "tauri_wasm_frontend")]
+pub struct BatchResults {
+    results: Vec<Option<Result<Result<Payload, Value>, ConversionError>>>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl BatchResults {
+    /// The result of `item`, with the same errors as `TauriCommand::send`.
+    pub fn get<Response: TauriResult, Error: MsgSafe>(
+        &self,
+        item: BatchItem<Response, Error>,
+    ) -> Result<Response, CommandError<Error>> {
+        match self.results.get(item.index) {
+            Some(Some(Ok(Ok(payload)))) => Ok(payload.clone().decode()?),
+            Some(Some(Ok(Err(rejection)))) => Err(CommandError::from_rejection(rejection.clone())),
+            Some(Some(Err(err))) => Err(CommandError::Conversion(err.clone())),
+            Some(None) | None => Err(CommandError::Invoke(String::from(
+                "The backend did not answer this command",
+            ))),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+/// Handles the commands of a batch with `dispatch`, which gets the name and the serialized
+/// command of every command and handles it like the backend would on its own.
+/// Called by the batch command generated by `slvr_rust_lib_build` and by `HttpRouter`.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub async fn run_batch<Dispatch, Fut>(
+    request: BatchRequest,
+    dispatch: Dispatch,
+) -> Vec<CommandResult>
+where
+    Dispatch: Fn(String, Value) -> Fut,
+    Fut: Future<Output = CommandResult> + Send,
+{
+    let commands = request
+        .commands
+        .into_iter()
+        .map(|command| dispatch(command.command, command.args));
+
+    match request.mode {
+        BatchMode::Concurrent => futures::future::join_all(commands).await,
+        BatchMode::InOrder => {
+            let mut results = Vec::new();
+            for command in commands {
+                results.push(command.await);
+            }
+            results
+        }
+    }
+}
+
+/// Deserializes the command of a batch, failures are rejected like tauri rejects invalid arguments.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn batch_args<C: serde::de::DeserializeOwned>(args: Value) -> Result<C, Value> {
+    serde_json::from_value(args).map_err(|err| Value::String(err.to_string()))
+}
+
+/// Rejects a batched command that the window the batch came from may not send on its own, called
+/// by the batch command generated by `slvr_rust_lib_build` with the `windows` of the command.
+/// `*` and `?` match any characters of the label, like in tauri capabilities.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn check_window(command_name: &str, windows: &[&str], window_label: &str) -> Result<(), Value> {
+    if windows
+        .iter()
+        .any(|window| label_matches(window.as_bytes(), window_label.as_bytes()))
+    {
+        Ok(())
+    } else {
+        Err(Value::String(format!(
+            "Command {command_name} not allowed on window {window_label}"
+        )))
+    }
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+fn label_matches(pattern: &[u8], label: &[u8]) -> bool {
+    match (pattern.first(), label.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            label_matches(&pattern[1..], label)
+                || (!label.is_empty() && label_matches(pattern, &label[1..]))
+        }
+        (Some(b'?'), Some(_)) => label_matches(&pattern[1..], &label[1..]),
+        (Some(expected), Some(found)) if expected == found => {
+            label_matches(&pattern[1..], &label[1..])
+        }
+        _ => false,
+    }
+}
+
+/// The rejection of a batched command the backend does not know.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn unknown_command(command_name: &str) -> Value {
+    Value::String(format!("Command {command_name} not found"))
+}
@@ -0,0 +1,292 @@
+#[cfg(feature = "tauri_wasm_backend")]
+use crate::tauri_wasm::context::CommandContext;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "tauri_wasm_backend")]
+use std::collections::HashMap;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::rc::Rc;
+#[cfg(feature = "tauri_wasm_backend")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "tauri_wasm_backend")]
+use std::sync::{Arc, LazyLock, Mutex};
+#[cfg(any(feature = "tauri_wasm_frontend", feature = "tauri_wasm_backend"))]
+use std::task::{Poll, Waker};
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::time::Duration;
+#[cfg(feature = "tauri_wasm_frontend")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The command the frontend sends to cancel a command it sent earlier, handled by `tauri_commands!()`.
+pub const CANCEL_COMMAND_NAME: &str = "__tauri_wasm_cancel";
+
+#[cfg(feature = "tauri_wasm_frontend")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32) -> i32;
+}
+
+/// How `TauriCommand::send_with` sends a command.
+#[cfg(feature = "tauri_wasm_frontend")]
+#[derive(Clone, Default)]
+pub struct SendOptions {
+    timeout: Option<Duration>,
+    abort_handle: Option<AbortHandle>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl SendOptions {
+    pub fn new() -> Self {
+        SendOptions::default()
+    }
+
+    /// Gives up with `CommandError::Timeout` if the backend did not answer within `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Gives up with `CommandError::Cancelled` once `abort_handle` is aborted.
+    pub fn abort_handle(mut self, abort_handle: &AbortHandle) -> Self {
+        self.abort_handle = Some(abort_handle.clone());
+        self
+    }
+
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.abort_handle
+            .as_ref()
+            .is_some_and(AbortHandle::is_aborted)
+    }
+
+    /// resolves once the command should be given up on, never if there is no timeout or abort handle
+    pub(crate) async fn interrupted(&self) -> Interruption {
+        let aborted = async {
+            match &self.abort_handle {
+                Some(abort_handle) => abort_handle.aborted().await,
+                None => std::future::pending().await,
+            }
+        };
+        let timed_out = async {
+            match self.timeout {
+                Some(timeout) => sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        match futures::future::select(std::pin::pin!(aborted), std::pin::pin!(timed_out)).await {
+            futures::future::Either::Left(_) => Interruption::Cancelled,
+            futures::future::Either::Right(_) => Interruption::Timeout,
+        }
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+pub(crate) enum Interruption {
+    Timeout,
+    Cancelled,
+}
+
+/// Cancels the commands sent with it, see `SendOptions::abort_handle`.
+///
+/// The backend sees the cancellation through `CommandContext::cancellation`,
+/// a handle can be shared by many commands, ie: every command of a page.
+#[cfg(feature = "tauri_wasm_frontend")]
+#[derive(Clone, Default)]
+pub struct AbortHandle {
+    state: Rc<RefCell<AbortState>>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+#[derive(Default)]
+struct AbortState {
+    aborted: bool,
+    wakers: Vec<Waker>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl AbortHandle {
+    pub fn new() -> Self {
+        AbortHandle::default()
+    }
+
+    pub fn abort(&self) {
+        let wakers = {
+            let mut state = self.state.borrow_mut();
+            state.aborted = true;
+            std::mem::take(&mut state.wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.state.borrow().aborted
+    }
+
+    async fn aborted(&self) {
+        std::future::poll_fn(|cx| {
+            let mut state = self.state.borrow_mut();
+            if state.aborted {
+                return Poll::Ready(());
+            }
+            if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+thread_local! {
+    static NEXT_REQUEST_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// the id the backend knows a cancellable command by, unique within the window
+#[cfg(feature = "tauri_wasm_frontend")]
+pub(crate) fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.with(|next| next.replace(next.get() + 1))
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+async fn sleep(duration: Duration) {
+    let milliseconds = i32::try_from(duration.as_millis()).unwrap_or(i32::MAX);
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, milliseconds);
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Tells a command handler that the frontend gave up on the command, see `CommandContext::cancellation`.
+///
+/// Cancellation is cooperative, long running handlers check `is_cancelled` between steps or race
+/// their work against `cancelled`:
+/// ```ignore
+/// for file in files {
+///     if context.cancellation().is_cancelled() {
+///         break;
+///     }
+///     sink.send(scan(file))?;
+/// }
+/// ```
+#[cfg(feature = "tauri_wasm_backend")]
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut *self.state.wakers.lock().unwrap());
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the command is cancelled.
+    pub async fn cancelled(&self) {
+        std::future::poll_fn(|cx| {
+            let mut wakers = self.state.wakers.lock().unwrap();
+            // checked with the lock held so a `cancel` in between cannot be missed
+            if self.is_cancelled() {
+                return Poll::Ready(());
+            }
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// the commands that can still be cancelled, by window label and request id
+#[cfg(feature = "tauri_wasm_backend")]
+static IN_FLIGHT: LazyLock<Mutex<HashMap<(String, u64), CancellationToken>>> =
+    LazyLock::new(Mutex::default);
+
+/// Keeps a command cancellable until it is dropped, returned by `cancellable`.
+#[cfg(feature = "tauri_wasm_backend")]
+pub struct InFlight {
+    entry: Option<((String, u64), CancellationToken)>,
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some((key, token)) = &self.entry {
+            let mut in_flight = IN_FLIGHT.lock().unwrap();
+            // a reloaded page counts its request ids from the start again, so the key may be taken
+            // by a newer command of the same window
+            if in_flight
+                .get(key)
+                .is_some_and(|current| Arc::ptr_eq(&current.state, &token.state))
+            {
+                in_flight.remove(key);
+            }
+        }
+    }
+}
+
+/// Gives the context of a command the frontend sent with a request id a token that
+/// `CANCEL_COMMAND_NAME` can cancel, called by the generated tauri commands.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn cancellable<R: tauri::Runtime>(
+    context: CommandContext<R>,
+    request_id: Option<u64>,
+) -> (CommandContext<R>, InFlight) {
+    let key = request_id.zip(context.label().map(str::to_string));
+    let Some((request_id, label)) = key else {
+        return (context, InFlight { entry: None });
+    };
+
+    let token = CancellationToken::new();
+    let key = (label, request_id);
+    IN_FLIGHT.lock().unwrap().insert(key.clone(), token.clone());
+
+    (
+        context.with_cancellation(token.clone()),
+        InFlight {
+            entry: Some((key, token)),
+        },
+    )
+}
+
+/// Handles `CANCEL_COMMAND_NAME`, cancelling the command with the `requestId` sent by the same window.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn cancel_invoke<R: tauri::Runtime>(invoke: tauri::ipc::Invoke<R>) -> bool {
+    let request_id = match invoke.message.payload() {
+        tauri::ipc::InvokeBody::Json(payload) => {
+            payload.get("requestId").and_then(serde_json::Value::as_u64)
+        }
+        _ => None,
+    };
+
+    if let Some(request_id) = request_id {
+        let key = (invoke.message.webview().label().to_string(), request_id);
+        if let Some(token) = IN_FLIGHT.lock().unwrap().remove(&key) {
+            token.cancel();
+        }
+    }
+
+    invoke.resolver.resolve(());
+    true
+}
@@ -1,29 +1,178 @@
-use crate::tauri_wasm::{Message, MsgSafe};
-use wasm_bindgen::JsValue;
-use wasm_bindgen::prelude::wasm_bindgen;
-
 #[cfg(feature = "tauri_wasm_frontend")]
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
-    async fn invoke(cmd: &str) -> JsValue;
-
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
-    async fn invoke_args(cmd: &str, args: JsValue) -> JsValue;
-}
+use crate::tauri_wasm::cancellation::{Interruption, SendOptions, next_request_id};
+#[cfg(feature = "tauri_wasm_backend")]
+use crate::tauri_wasm::context::CommandContext;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::transport::{current_transport, invoke_command};
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::try_from_json;
+use crate::tauri_wasm::{ConversionError, MsgSafe};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 
-pub trait TauriCommand<Result: TauriResult>: MsgSafe {
+/// A command that can be sent from the frontend and handled by the backend.
+///
+/// `Error` is the domain error returned by the backend, commands that cannot fail can leave it
+/// as the default `NoError`.
+pub trait TauriCommand<Response: TauriResult, Error: MsgSafe = NoError>: MsgSafe {
     const COMMAND_NAME: &'static str;
 
+    /// Sends the command to the backend and waits for its response.
+    ///
+    /// Returns `Err(CommandError::Backend)` if the backend handled the command and returned an error,
+    /// `Err(CommandError::Invoke)` if tauri rejected the invoke
+    /// and `Err(CommandError::Conversion)` if the command or the response could not be converted.
+    ///
+    /// The command goes through the transport set with `transport::set_transport`, tauri's `invoke` by default.
     #[cfg(feature = "tauri_wasm_frontend")]
-    fn send(self) -> impl Future<Output = Result> {
+    fn send(self) -> impl Future<Output = Result<Response, CommandError<Error>>> {
         async move {
-            Result::from_js(invoke_args(Self::COMMAND_NAME, self.to_js()).await)
+            let response = invoke_command(&*current_transport(), Self::COMMAND_NAME, &self, None)?;
+
+            match response.await {
+                Ok(payload) => Ok(payload.decode()?),
+                Err(rejection) => Err(CommandError::from_rejection(rejection)),
+            }
         }
     }
 
+    /// Sends the command like `send`, giving up with `CommandError::Timeout` or `CommandError::Cancelled`
+    /// when `options` says so:
+    /// ```ignore
+    /// let abort_handle = AbortHandle::new();
+    /// let response = ScanDirectory { path }
+    ///     .send_with(SendOptions::new().timeout(Duration::from_secs(30)).abort_handle(&abort_handle))
+    ///     .await;
+    /// ```
+    /// Giving up also cancels the command on the backend, see `CommandContext::cancellation`.
+    #[cfg(feature = "tauri_wasm_frontend")]
+    fn send_with(
+        self,
+        options: SendOptions,
+    ) -> impl Future<Output = Result<Response, CommandError<Error>>> {
+        async move {
+            if options.is_aborted() {
+                return Err(CommandError::Cancelled);
+            }
+
+            let transport = current_transport();
+            let request_id = next_request_id();
+            let response =
+                invoke_command(&*transport, Self::COMMAND_NAME, &self, Some(request_id))?;
+
+            match futures::future::select(response, std::pin::pin!(options.interrupted())).await {
+                futures::future::Either::Left((Ok(payload), _)) => Ok(payload.decode()?),
+                futures::future::Either::Left((Err(rejection), _)) => {
+                    Err(CommandError::from_rejection(rejection))
+                }
+                futures::future::Either::Right((interruption, _)) => {
+                    transport.cancel(Self::COMMAND_NAME, request_id);
+                    Err(match interruption {
+                        Interruption::Timeout => CommandError::Timeout,
+                        Interruption::Cancelled => CommandError::Cancelled,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Handles the command, `context` gives access to the app, the invoking window, managed state
+    /// and the cancellation of the command.
     #[cfg(feature = "tauri_wasm_backend")]
-    fn handle(self) -> impl Future<Output = Result>;
+    fn handle<R: tauri::Runtime>(
+        self,
+        context: CommandContext<R>,
+    ) -> impl Future<Output = Result<Response, Error>> + Send;
 }
 
 pub trait TauriResult: MsgSafe {}
+
+/// The `COMMAND_NAME` of one instance of a generic command,
+/// implemented by `#[tauri_command_impl(instances(...))]` for every type it lists.
+pub trait TauriCommandInstance {
+    const COMMAND_NAME: &'static str;
+}
+
+/// Describes a command found by `slvr_rust_lib_build::tauri_wasm::resolve_message_structs`.
+#[derive(Debug, Clone, Copy)]
+pub struct TauriCommandInfo {
+    pub struct_name: &'static str,
+    pub command_name: &'static str,
+    /// the item type for streaming commands
+    pub response_type: &'static str,
+    /// whether the command is a `TauriStreamCommand`
+    pub streaming: bool,
+}
+
+/// The error type of commands that cannot fail.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum NoError {}
+
+impl MsgSafe for NoError {}
+
+/// The form a backend domain error takes when it crosses the ipc boundary.
+///
+/// Wrapping the error lets the frontend tell it apart from tauri rejecting the invoke itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendError<E> {
+    pub backend_error: E,
+}
+
+impl<E> BackendError<E> {
+    pub fn new(backend_error: E) -> Self {
+        BackendError { backend_error }
+    }
+}
+
+impl<E: MsgSafe> MsgSafe for BackendError<E> {}
+
+#[derive(Debug)]
+pub enum CommandError<E> {
+    /// The backend handled the command and returned an error.
+    Backend(E),
+    /// Tauri rejected the invoke, ie: the command does not exist or the window is not allowed to call it.
+    Invoke(String),
+    /// The command or its response could not be converted.
+    Conversion(ConversionError),
+    /// The backend did not answer within the timeout given to `send_with`.
+    Timeout,
+    /// The command was cancelled with the `AbortHandle` given to `send_with`.
+    Cancelled,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<E: MsgSafe> CommandError<E> {
+    pub(crate) fn from_rejection(rejection: serde_json::Value) -> Self {
+        match rejection {
+            serde_json::Value::Object(ref object) if object.contains_key("backend_error") => {
+                match try_from_json::<BackendError<E>>(rejection) {
+                    Ok(error) => CommandError::Backend(error.backend_error),
+                    Err(err) => CommandError::Conversion(err),
+                }
+            }
+            serde_json::Value::String(message) => CommandError::Invoke(message),
+            rejection => CommandError::Invoke(rejection.to_string()),
+        }
+    }
+}
+
+impl<E> From<ConversionError> for CommandError<E> {
+    fn from(value: ConversionError) -> Self {
+        CommandError::Conversion(value)
+    }
+}
+
+impl<E: Debug> Display for CommandError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::Backend(err) => write!(f, "The backend returned an error: {err:?}"),
+            CommandError::Invoke(message) => write!(f, "Tauri rejected the invoke: {message}"),
+            CommandError::Conversion(err) => Display::fmt(err, f),
+            CommandError::Timeout => write!(f, "The backend did not answer in time"),
+            CommandError::Cancelled => write!(f, "The command was cancelled"),
+        }
+    }
+}
+
+impl<E: Debug> Error for CommandError<E> {}
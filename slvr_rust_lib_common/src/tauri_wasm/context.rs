@@ -0,0 +1,83 @@
+use crate::tauri_wasm::cancellation::CancellationToken;
+use tauri::{AppHandle, Manager, Runtime, State, WebviewWindow};
+
+/// What the backend knows about an invoke, passed to `TauriCommand::handle` by the generated command.
+///
+/// Managed state is reached through `state`/`try_state` instead of global statics:
+/// ```ignore
+/// async fn handle<R: tauri::Runtime>(self, context: CommandContext<R>) -> Result<Resp, NoError> {
+///     let database = context.state::<Database>();
+///     ...
+/// }
+/// ```
+pub struct CommandContext<R: Runtime> {
+    app: AppHandle<R>,
+    window: Option<WebviewWindow<R>>,
+    cancellation: CancellationToken,
+}
+
+impl<R: Runtime> CommandContext<R> {
+    pub fn new(app: AppHandle<R>, window: WebviewWindow<R>) -> Self {
+        CommandContext {
+            app,
+            window: Some(window),
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// A context for commands that did not come from a window, ie: through `HttpRouter`.
+    pub fn without_window(app: AppHandle<R>) -> Self {
+        CommandContext {
+            app,
+            window: None,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Replaces the token that tells the handler the command was cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn app(&self) -> &AppHandle<R> {
+        &self.app
+    }
+
+    /// The window the command was invoked from, `None` if it did not come from a window.
+    pub fn window(&self) -> Option<&WebviewWindow<R>> {
+        self.window.as_ref()
+    }
+
+    /// The label of the window the command was invoked from.
+    pub fn label(&self) -> Option<&str> {
+        self.window.as_ref().map(|window| window.label())
+    }
+
+    /// Cancelled when the frontend gives up on the command, see `TauriCommand::send_with`.
+    /// Commands that were not sent with a request id, ie: through `HttpRouter`, are never cancelled.
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Gets the state managed for `T`.
+    ///
+    /// Panics if nothing was managed for `T`, use `try_state` for a non-panicking version.
+    pub fn state<T: Send + Sync + 'static>(&self) -> State<'_, T> {
+        self.app.state::<T>()
+    }
+
+    pub fn try_state<T: Send + Sync + 'static>(&self) -> Option<State<'_, T>> {
+        self.app.try_state::<T>()
+    }
+}
+
+impl<R: Runtime> Clone for CommandContext<R> {
+    fn clone(&self) -> Self {
+        CommandContext {
+            app: self.app.clone(),
+            window: self.window.clone(),
+            cancellation: self.cancellation.clone(),
+        }
+    }
+}
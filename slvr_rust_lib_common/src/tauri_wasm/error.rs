@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// An error produced while converting a message between its rust and js representations.
+///
+/// `type_name` is the rust type being converted and `path` is the serde path to the field
+/// that could not be converted (`.` if the failure was at the top level).
+/// Binary messages, see `MsgSafe::BINARY`, are encoded without keeping track of the path.
+#[derive(Debug, Clone)]
+pub enum ConversionError {
+    ToJs {
+        type_name: &'static str,
+        path: String,
+        message: String,
+    },
+    FromJs {
+        type_name: &'static str,
+        path: String,
+        message: String,
+    },
+    ToBinary {
+        type_name: &'static str,
+        message: String,
+    },
+    FromBinary {
+        type_name: &'static str,
+        message: String,
+    },
+}
+
+impl ConversionError {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConversionError::ToJs { type_name, .. } => type_name,
+            ConversionError::FromJs { type_name, .. } => type_name,
+            ConversionError::ToBinary { type_name, .. } => type_name,
+            ConversionError::FromBinary { type_name, .. } => type_name,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            ConversionError::ToJs { path, .. } => path,
+            ConversionError::FromJs { path, .. } => path,
+            ConversionError::ToBinary { .. } | ConversionError::FromBinary { .. } => ".",
+        }
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::ToJs {
+                type_name,
+                path,
+                message,
+            } => write!(
+                f,
+                "Could not convert rust object `{type_name}` to js object at `{path}`: {message}"
+            ),
+            ConversionError::FromJs {
+                type_name,
+                path,
+                message,
+            } => write!(
+                f,
+                "Could not convert js object to rust object `{type_name}` at `{path}`: {message}"
+            ),
+            ConversionError::ToBinary { type_name, message } => write!(
+                f,
+                "Could not encode rust object `{type_name}` as binary: {message}"
+            ),
+            ConversionError::FromBinary { type_name, message } => write!(
+                f,
+                "Could not decode binary to rust object `{type_name}`: {message}"
+            ),
+        }
+    }
+}
+
+impl Error for ConversionError {}
@@ -0,0 +1,122 @@
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::Message;
+use crate::tauri_wasm::MsgSafe;
+#[cfg(feature = "tauri_wasm_frontend")]
+use futures::Stream;
+#[cfg(feature = "tauri_wasm_frontend")]
+use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::pin::Pin;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::task::{Context, Poll};
+#[cfg(feature = "tauri_wasm_frontend")]
+use wasm_bindgen::JsValue;
+#[cfg(feature = "tauri_wasm_frontend")]
+use wasm_bindgen::prelude::{Closure, wasm_bindgen};
+
+#[cfg(feature = "tauri_wasm_frontend")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+    fn listen_js(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn console_warn(message: &str);
+}
+
+/// An event that the backend can push to the frontend.
+pub trait TauriEvent: MsgSafe {
+    const EVENT_NAME: &'static str;
+
+    /// Listens for this event, the listener is removed when the stream is dropped.
+    ///
+    /// Events with a payload that cannot be converted to `Self` are skipped with a console warning.
+    #[cfg(feature = "tauri_wasm_frontend")]
+    fn listen() -> EventStream<Self>
+    where
+        Self: 'static,
+    {
+        EventStream::new(Self::EVENT_NAME)
+    }
+
+    /// Emits this event to every window.
+    #[cfg(feature = "tauri_wasm_backend")]
+    fn emit<R: tauri::Runtime>(&self, app: &tauri::AppHandle<R>) -> tauri::Result<()> {
+        tauri::Emitter::emit(app, Self::EVENT_NAME, self)
+    }
+
+    /// Emits this event to the window, webview or event listener with the label `label`.
+    #[cfg(feature = "tauri_wasm_backend")]
+    fn emit_to<R: tauri::Runtime>(
+        &self,
+        app: &tauri::AppHandle<R>,
+        label: &str,
+    ) -> tauri::Result<()> {
+        tauri::Emitter::emit_to(app, label, Self::EVENT_NAME, self)
+    }
+}
+
+/// A stream of `TauriEvent`s, see `TauriEvent::listen`.
+#[cfg(feature = "tauri_wasm_frontend")]
+pub struct EventStream<E> {
+    receiver: UnboundedReceiver<E>,
+    listener: Option<Listener>,
+}
+
+/// the js side of an `EventStream`, kept alive until the stream is dropped
+#[cfg(feature = "tauri_wasm_frontend")]
+struct Listener {
+    handler: Closure<dyn FnMut(JsValue)>,
+    /// resolves to the function that removes the listener
+    unlisten: js_sys::Promise,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<E: MsgSafe + 'static> EventStream<E> {
+    fn new(event_name: &'static str) -> Self {
+        let (sender, receiver) = unbounded();
+
+        let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload"))
+                .unwrap_or(JsValue::UNDEFINED);
+
+            match E::try_from_js(payload) {
+                // the receiver is only gone while the listener is being removed
+                Ok(event) => {
+                    let _ = sender.unbounded_send(event);
+                }
+                Err(err) => console_warn(&format!("Skipping event `{event_name}`: {err}")),
+            }
+        });
+        let unlisten = listen_js(event_name, &handler);
+
+        EventStream {
+            receiver,
+            listener: Some(Listener { handler, unlisten }),
+        }
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<E> Stream for EventStream<E> {
+    type Item = E;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<E> Drop for EventStream<E> {
+    fn drop(&mut self) {
+        if let Some(Listener { handler, unlisten }) = self.listener.take() {
+            // the handler has to outlive the listener, so it is only dropped once unlisten has been called
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(unlisten) = wasm_bindgen_futures::JsFuture::from(unlisten).await {
+                    let _ = js_sys::Function::from(unlisten).call0(&JsValue::NULL);
+                }
+                drop(handler);
+            });
+        }
+    }
+}
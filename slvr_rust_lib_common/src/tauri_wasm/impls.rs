@@ -0,0 +1,120 @@
+//! `MsgSafe` and `TauriResult` for std types and the optional crates,
+//! so commands can answer with `Vec<Item>`, `Option<T>`, `()` and the like without a newtype.
+
+use crate::tauri_wasm::MsgSafe;
+use crate::tauri_wasm::command::TauriResult;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+macro_rules! msg_safe {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl MsgSafe for $type {}
+            impl TauriResult for $type {}
+        )*
+    };
+}
+
+macro_rules! msg_safe_tuples {
+    ($(($($param:ident),+)),* $(,)?) => {
+        $(
+            impl<$($param: MsgSafe),+> MsgSafe for ($($param,)+) {}
+            impl<$($param: MsgSafe),+> TauriResult for ($($param,)+) {}
+        )*
+    };
+}
+
+msg_safe!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+    std::path::PathBuf,
+    std::time::Duration,
+    std::time::SystemTime,
+    std::net::IpAddr,
+    std::net::SocketAddr,
+    serde_json::Value,
+);
+
+// std implements `Debug` for tuples of up to 12 elements
+msg_safe_tuples!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+);
+
+impl<T: MsgSafe> MsgSafe for Option<T> {}
+impl<T: MsgSafe> TauriResult for Option<T> {}
+
+impl<T: MsgSafe, E: MsgSafe> MsgSafe for Result<T, E> {}
+impl<T: MsgSafe, E: MsgSafe> TauriResult for Result<T, E> {}
+
+impl<T: MsgSafe> MsgSafe for Box<T> {}
+impl<T: MsgSafe> TauriResult for Box<T> {}
+
+impl<T: MsgSafe> MsgSafe for Vec<T> {}
+impl<T: MsgSafe> TauriResult for Vec<T> {}
+
+impl<T: MsgSafe> MsgSafe for VecDeque<T> {}
+impl<T: MsgSafe> TauriResult for VecDeque<T> {}
+
+impl<T: MsgSafe + Ord> MsgSafe for BTreeSet<T> {}
+impl<T: MsgSafe + Ord> TauriResult for BTreeSet<T> {}
+
+impl<T: MsgSafe + Eq + Hash> MsgSafe for HashSet<T> {}
+impl<T: MsgSafe + Eq + Hash> TauriResult for HashSet<T> {}
+
+impl<K: MsgSafe + Ord, V: MsgSafe> MsgSafe for BTreeMap<K, V> {}
+impl<K: MsgSafe + Ord, V: MsgSafe> TauriResult for BTreeMap<K, V> {}
+
+impl<K: MsgSafe + Eq + Hash, V: MsgSafe> MsgSafe for HashMap<K, V> {}
+impl<K: MsgSafe + Eq + Hash, V: MsgSafe> TauriResult for HashMap<K, V> {}
+
+#[cfg(feature = "tauri_wasm_uuid")]
+msg_safe!(uuid::Uuid);
+
+#[cfg(feature = "tauri_wasm_url")]
+msg_safe!(url::Url);
+
+#[cfg(feature = "tauri_wasm_chrono")]
+msg_safe!(
+    chrono::NaiveDate,
+    chrono::NaiveTime,
+    chrono::NaiveDateTime,
+    chrono::DateTime<chrono::Utc>,
+    chrono::DateTime<chrono::FixedOffset>,
+    chrono::DateTime<chrono::Local>,
+);
+
+#[cfg(feature = "tauri_wasm_time")]
+msg_safe!(
+    time::Date,
+    time::Time,
+    time::PrimitiveDateTime,
+    time::OffsetDateTime,
+    time::Duration,
+);
@@ -0,0 +1,192 @@
+use crate::tauri_wasm::MsgSafe;
+use crate::tauri_wasm::context::CommandContext;
+use crate::tauri_wasm::payload::Payload;
+use futures::FutureExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use tauri::Runtime;
+
+/// The serialized result of a command, the response or the rejection sent to the frontend.
+pub type CommandResult = Result<Payload, Value>;
+
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = CommandResult> + Send + 'a>>;
+
+/// Runs around every command handled by the backend, both the generated tauri commands and `HttpRouter`.
+///
+/// A middleware gets the call before the command is handled and decides whether and how to run
+/// the rest of the chain, so it can time it, wrap it in a span, or reject it without running it:
+/// ```ignore
+/// struct MainWindowOnly;
+///
+/// impl CommandMiddleware for MainWindowOnly {
+///     fn call<'a>(&'a self, call: &'a CommandCall, next: Next<'a>) -> CommandFuture<'a> {
+///         if call.window_label.as_deref() == Some("main") {
+///             next.run()
+///         } else {
+///             Box::pin(async { Err(Value::from("not allowed")) })
+///         }
+///     }
+/// }
+/// ```
+/// Rejections that are strings reach the frontend as `CommandError::Invoke`.
+pub trait CommandMiddleware: Send + Sync + 'static {
+    fn call<'a>(&'a self, call: &'a CommandCall, next: Next<'a>) -> CommandFuture<'a>;
+}
+
+/// A command on its way to its handler.
+#[derive(Debug, Clone)]
+pub struct CommandCall {
+    pub command_name: &'static str,
+    /// the command as it was sent by the frontend, `Value::Null` for binary commands
+    pub payload: Value,
+    /// the label of the window the command was invoked from, `None` for `HttpRouter`
+    pub window_label: Option<String>,
+}
+
+/// The rest of the middleware chain, ending with the command handler.
+pub struct Next<'a> {
+    call: &'a CommandCall,
+    middlewares: &'a [Arc<dyn CommandMiddleware>],
+    handler: CommandFuture<'a>,
+}
+
+impl<'a> Next<'a> {
+    /// Runs the next middleware, or the handler once every middleware ran.
+    pub fn run(self) -> CommandFuture<'a> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => middleware.call(
+                self.call,
+                Next {
+                    call: self.call,
+                    middlewares,
+                    handler: self.handler,
+                },
+            ),
+            None => self.handler,
+        }
+    }
+}
+
+/// The middleware chain, registered once as managed state:
+/// ```ignore
+/// tauri::Builder::default()
+///     .manage(CommandMiddlewares::new().with(CatchPanic).with(MainWindowOnly))
+///     .invoke_handler(tauri_commands!())
+/// ```
+/// Middlewares run in the order they were added, the first one is the outermost.
+#[derive(Clone, Default)]
+pub struct CommandMiddlewares {
+    middlewares: Vec<Arc<dyn CommandMiddleware>>,
+}
+
+impl CommandMiddlewares {
+    pub fn new() -> Self {
+        CommandMiddlewares::default()
+    }
+
+    pub fn with(mut self, middleware: impl CommandMiddleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    pub fn run<'a>(
+        &'a self,
+        call: &'a CommandCall,
+        handler: CommandFuture<'a>,
+    ) -> CommandFuture<'a> {
+        Next {
+            call,
+            middlewares: &self.middlewares,
+            handler,
+        }
+        .run()
+    }
+}
+
+/// Turns a panic in the rest of the chain into a rejection with the panic message
+/// instead of leaving the frontend waiting for a response that never comes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CatchPanic;
+
+impl CommandMiddleware for CatchPanic {
+    fn call<'a>(&'a self, call: &'a CommandCall, next: Next<'a>) -> CommandFuture<'a> {
+        Box::pin(async move {
+            AssertUnwindSafe(next.run())
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|panic| {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| String::from("unknown panic"));
+                    Err(Value::String(format!(
+                        "Command {} panicked: {message}",
+                        call.command_name
+                    )))
+                })
+        })
+    }
+}
+
+/// Handles a command through the `CommandMiddlewares` managed by the app, called by the generated
+/// tauri commands and by `HttpRouter`. Without middlewares the command is handled directly.
+#[doc(hidden)]
+pub async fn run_command<R, Command, Resp, Error, Handler, Fut>(
+    command_name: &'static str,
+    command: Command,
+    context: CommandContext<R>,
+    handler: Handler,
+) -> CommandResult
+where
+    R: Runtime,
+    Command: MsgSafe,
+    Resp: MsgSafe,
+    Error: Serialize,
+    Handler: FnOnce(Command, CommandContext<R>) -> Fut + Send,
+    Fut: Future<Output = Result<Resp, Error>> + Send,
+{
+    let middlewares = context
+        .try_state::<CommandMiddlewares>()
+        .map(|middlewares| (*middlewares).clone())
+        .filter(|middlewares| !middlewares.middlewares.is_empty());
+
+    let Some(middlewares) = middlewares else {
+        return serialize_result(handler(command, context).await);
+    };
+
+    // binary commands carry large buffers, which are not worth turning into json
+    let payload = if Command::BINARY {
+        Value::Null
+    } else {
+        serde_json::to_value(&command).unwrap_or(Value::Null)
+    };
+    let call = CommandCall {
+        command_name,
+        payload,
+        window_label: context.label().map(str::to_string),
+    };
+
+    // the handler is only called once the chain reaches it, so a panic while it builds its future
+    // happens inside the middlewares too and `CatchPanic` can catch it
+    middlewares
+        .run(
+            &call,
+            Box::pin(async move { serialize_result(handler(command, context).await) }),
+        )
+        .await
+}
+
+fn serialize_result<Resp: MsgSafe, Error: Serialize>(result: Result<Resp, Error>) -> CommandResult {
+    let serialize_error = |err: &dyn std::fmt::Display| {
+        Value::String(format!("Could not serialize the result: {err}"))
+    };
+
+    match result {
+        Ok(response) => Payload::encode(&response).map_err(|err| serialize_error(&err)),
+        Err(err) => Err(serde_json::to_value(err).map_err(|err| serialize_error(&err))?),
+    }
+}
@@ -0,0 +1,263 @@
+use crate::tauri_wasm::batch::{BATCH_COMMAND_NAME, BatchRequest};
+use crate::tauri_wasm::command::{BackendError, TauriCommand, TauriResult};
+use crate::tauri_wasm::payload::Payload;
+use crate::tauri_wasm::schema::{HANDSHAKE_COMMAND_NAME, SchemaSummary};
+use crate::tauri_wasm::transport::{Transport, set_transport};
+use crate::tauri_wasm::{ConversionError, MsgSafe, try_to_json};
+use futures::future::LocalBoxFuture;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+type MockHandler = Box<dyn FnMut(Payload) -> Result<Payload, Value>>;
+
+/// An in-process backend for testing frontend code natively, no webview needed.
+///
+/// Installing it replaces the transport of the current thread, so every test gets its own mock:
+/// ```ignore
+/// let backend = MockBackend::install();
+/// backend.respond::<CheckScreenshot, _, _>(CheckScreenshotResp { ok: true });
+///
+/// let response = futures::executor::block_on(CheckScreenshot { path }.send());
+///
+/// assert_eq!(backend.call_count(CheckScreenshot::COMMAND_NAME), 1);
+/// assert_eq!(backend.calls::<CheckScreenshot, _, _>()[0].path, path);
+/// ```
+/// Commands without a response are rejected like tauri rejects unknown commands,
+/// `pending` commands never answer, to test timeouts and cancellation.
+/// The commands of a `Batch` are answered and recorded as if they were sent one by one,
+/// the handshake of `check_schema` is answered with the schema given to `schema`.
+/// Streaming commands and events are not mocked, they always go through tauri.
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Rc<RefCell<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    handlers: HashMap<&'static str, MockHandler>,
+    pending: HashSet<&'static str>,
+    calls: Vec<MockCall>,
+    cancelled: Vec<&'static str>,
+    schema: Option<SchemaSummary>,
+}
+
+/// A command received by a `MockBackend`.
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    pub command_name: &'static str,
+    /// the command as the frontend encoded it, binary for `MsgSafe::BINARY` commands
+    pub args: Payload,
+}
+
+impl MockBackend {
+    /// Creates a mock backend and makes it the transport of the current thread.
+    pub fn install() -> Self {
+        let backend = MockBackend::default();
+        set_transport(backend.clone());
+        backend
+    }
+
+    /// Answers every `C` with a clone of `response`.
+    pub fn respond<C, Resp, Error>(&self, response: Resp)
+    where
+        C: TauriCommand<Resp, Error>,
+        Resp: TauriResult + Clone + 'static,
+        Error: MsgSafe,
+    {
+        self.handle::<C, Resp, Error>(move |_| Ok(response.clone()));
+    }
+
+    /// Answers every `C` with the backend error `error`.
+    pub fn fail<C, Resp, Error>(&self, error: Error)
+    where
+        C: TauriCommand<Resp, Error>,
+        Resp: TauriResult,
+        Error: MsgSafe + Clone + 'static,
+    {
+        self.handle::<C, Resp, Error>(move |_| Err(error.clone()));
+    }
+
+    /// Answers every `C` with whatever `handler` returns, replacing any earlier response for `C`.
+    pub fn handle<C, Resp, Error>(
+        &self,
+        mut handler: impl FnMut(C) -> Result<Resp, Error> + 'static,
+    ) where
+        C: TauriCommand<Resp, Error>,
+        Resp: TauriResult,
+        Error: MsgSafe,
+    {
+        let handler: MockHandler = Box::new(move |args| {
+            let command = args
+                .decode::<C>()
+                .map_err(|err| Value::String(err.to_string()))?;
+            let conversion_error = |err: ConversionError| Value::String(err.to_string());
+            match handler(command) {
+                Ok(response) => Payload::encode(&response).map_err(conversion_error),
+                Err(err) => {
+                    Err(try_to_json(&BackendError::new(err)).unwrap_or_else(conversion_error))
+                }
+            }
+        });
+
+        let mut state = self.state.borrow_mut();
+        state.pending.remove(C::COMMAND_NAME);
+        state.handlers.insert(C::COMMAND_NAME, handler);
+    }
+
+    /// Leaves every `C` waiting for a response that never comes, replacing any earlier response for `C`.
+    pub fn pending<C, Resp, Error>(&self)
+    where
+        C: TauriCommand<Resp, Error>,
+        Resp: TauriResult,
+        Error: MsgSafe,
+    {
+        let mut state = self.state.borrow_mut();
+        state.handlers.remove(C::COMMAND_NAME);
+        state.pending.insert(C::COMMAND_NAME);
+    }
+
+    /// Answers the handshake of `check_schema` with `schema`, ie: `TAURI_SCHEMA.summary()`.
+    pub fn schema(&self, schema: SchemaSummary) {
+        self.state.borrow_mut().schema = Some(schema);
+    }
+
+    /// Every command received so far, in the order they were sent.
+    pub fn all_calls(&self) -> Vec<MockCall> {
+        self.state.borrow().calls.clone()
+    }
+
+    /// The `C`s received so far, in the order they were sent.
+    ///
+    /// # Panics
+    /// Panics if a recorded call cannot be converted back to `C`.
+    pub fn calls<C, Resp, Error>(&self) -> Vec<C>
+    where
+        C: TauriCommand<Resp, Error>,
+        Resp: TauriResult,
+        Error: MsgSafe,
+    {
+        self.state
+            .borrow()
+            .calls
+            .iter()
+            .filter(|call| call.command_name == C::COMMAND_NAME)
+            .map(|call| {
+                call.args
+                    .clone()
+                    .decode()
+                    .unwrap_or_else(|err| panic!("{err}"))
+            })
+            .collect()
+    }
+
+    pub fn call_count(&self, command_name: &str) -> usize {
+        self.state
+            .borrow()
+            .calls
+            .iter()
+            .filter(|call| call.command_name == command_name)
+            .count()
+    }
+
+    /// How many times the frontend gave up on `command_name`, see `TauriCommand::send_with`.
+    pub fn cancel_count(&self, command_name: &str) -> usize {
+        self.state
+            .borrow()
+            .cancelled
+            .iter()
+            .filter(|cancelled| **cancelled == command_name)
+            .count()
+    }
+
+    /// answers every command of the batch with its own handler, like the backend does
+    fn invoke_batch(&self, args: Payload) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        let request = match args.decode::<BatchRequest>() {
+            Ok(request) => request,
+            Err(err) => return Box::pin(std::future::ready(Err(Value::String(err.to_string())))),
+        };
+
+        let results = request
+            .commands
+            .into_iter()
+            .map(|command| {
+                // the recorded calls need the `&'static str` the command was registered with
+                let command_name = {
+                    let state = self.state.borrow();
+                    state
+                        .handlers
+                        .keys()
+                        .chain(state.pending.iter())
+                        .find(|name| **name == command.command)
+                        .copied()
+                };
+                match command_name {
+                    Some(command_name) => self.invoke(command_name, Payload::Json(command.args)),
+                    None => Box::pin(std::future::ready(Err(Value::String(format!(
+                        "Command {} not found",
+                        command.command
+                    ))))),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Box::pin(async move {
+            let results = futures::future::join_all(results).await;
+            Payload::encode(&results).map_err(|err| Value::String(err.to_string()))
+        })
+    }
+}
+
+impl Transport for MockBackend {
+    fn invoke(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        if command_name == BATCH_COMMAND_NAME {
+            return self.invoke_batch(args);
+        }
+        if command_name == HANDSHAKE_COMMAND_NAME {
+            let result = match &self.state.borrow().schema {
+                Some(schema) => {
+                    Payload::encode(schema).map_err(|err| Value::String(err.to_string()))
+                }
+                None => Err(Value::String(format!("Command {command_name} not found"))),
+            };
+            return Box::pin(std::future::ready(result));
+        }
+
+        let handler = {
+            let mut state = self.state.borrow_mut();
+            state.calls.push(MockCall {
+                command_name,
+                args: args.clone(),
+            });
+            if state.pending.contains(command_name) {
+                return Box::pin(std::future::pending());
+            }
+            state.handlers.remove(command_name)
+        };
+
+        // the handler runs without the state borrowed, so it can look at the calls itself
+        let result = match handler {
+            Some(mut handler) => {
+                let result = handler(args);
+                self.state
+                    .borrow_mut()
+                    .handlers
+                    .entry(command_name)
+                    .or_insert(handler);
+                result
+            }
+            None => Err(Value::String(format!("Command {command_name} not found"))),
+        };
+
+        Box::pin(async move { result })
+    }
+
+    fn cancel(&self, command_name: &'static str, _request_id: u64) {
+        self.state.borrow_mut().cancelled.push(command_name);
+    }
+}
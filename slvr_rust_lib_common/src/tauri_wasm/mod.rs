@@ -1,29 +1,102 @@
-
-#[cfg(feature = "tauri_wasm_frontend")]
+pub mod batch;
+pub mod cancellation;
 pub mod command;
+#[cfg(feature = "tauri_wasm_backend")]
+pub mod context;
+mod error;
+pub mod event;
+mod impls;
+#[cfg(feature = "tauri_wasm_backend")]
+pub mod middleware;
+#[cfg(feature = "tauri_wasm_mock")]
+pub mod mock;
+pub mod payload;
+#[cfg(feature = "tauri_wasm_http_backend")]
+pub mod router;
+pub mod schema;
+pub mod stream;
+#[cfg(feature = "tauri_wasm_frontend")]
+pub mod transport;
 
-use std::fmt::Debug;
-use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
 use wasm_bindgen::JsValue;
 
-pub trait MsgSafe: Sized + Send + Sync + Debug + Serialize + DeserializeOwned {}
+pub use error::ConversionError;
+
+pub trait MsgSafe: Sized + Send + Sync + Debug + Serialize + DeserializeOwned {
+    /// Whether commands and responses of this type are sent as compact binary instead of json,
+    /// set with `#[tauri_command(binary)]` or `#[tauri_response(binary)]`, see `payload::Payload`.
+    const BINARY: bool = false;
+}
+
+pub trait Message: Sized {
+    fn try_to_js(&self) -> Result<JsValue, ConversionError>;
 
-pub trait Message {
-    fn to_js(&self) -> JsValue;
+    fn try_from_js(from: JsValue) -> Result<Self, ConversionError>;
 
-    fn from_js<'a>(from: JsValue) -> Self;
+    /// # Panics
+    /// Panics if the conversion fails, see `Message::try_to_js` for a fallible alternative.
+    fn to_js(&self) -> JsValue {
+        self.try_to_js().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// # Panics
+    /// Panics if the conversion fails, see `Message::try_from_js` for a fallible alternative.
+    fn from_js(from: JsValue) -> Self {
+        Self::try_from_js(from).unwrap_or_else(|err| panic!("{err}"))
+    }
 }
 
 impl<T: MsgSafe> Message for T {
-    fn to_js(&self) -> JsValue {
-        serde_wasm_bindgen::to_value(self).expect("Could not convert rust object to js object")
+    fn try_to_js(&self) -> Result<JsValue, ConversionError> {
+        let serializer = serde_wasm_bindgen::Serializer::new();
+        serde_path_to_error::serialize(self, &serializer).map_err(|err| ConversionError::ToJs {
+            type_name: std::any::type_name::<T>(),
+            path: err.path().to_string(),
+            message: err.inner().to_string(),
+        })
     }
 
-    fn from_js(value: JsValue) -> Self {
-        serde_wasm_bindgen::from_value::<Self>(value)
-            .expect("Could not convert js object to rust object")
+    fn try_from_js(value: JsValue) -> Result<Self, ConversionError> {
+        let deserializer = serde_wasm_bindgen::Deserializer::from(value);
+        serde_path_to_error::deserialize(deserializer).map_err(|err| ConversionError::FromJs {
+            type_name: std::any::type_name::<T>(),
+            path: err.path().to_string(),
+            message: err.inner().to_string(),
+        })
     }
 }
 
+/// converts a message to the json value the transports send, reported like `Message::try_to_js`
+pub(crate) fn try_to_json<T: MsgSafe>(message: &T) -> Result<serde_json::Value, ConversionError> {
+    serde_path_to_error::serialize(message, serde_json::value::Serializer).map_err(|err| {
+        ConversionError::ToJs {
+            type_name: std::any::type_name::<T>(),
+            path: err.path().to_string(),
+            message: err.inner().to_string(),
+        }
+    })
+}
 
+/// converts a message straight to the plain js objects tauri reads, without a `serde_json::Value`
+/// in between, reported like `Message::try_to_js`
+#[cfg(feature = "tauri_wasm_frontend")]
+pub(crate) fn try_to_js_json<T: MsgSafe>(message: &T) -> Result<JsValue, ConversionError> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    serde_path_to_error::serialize(message, &serializer).map_err(|err| ConversionError::ToJs {
+        type_name: std::any::type_name::<T>(),
+        path: err.path().to_string(),
+        message: err.inner().to_string(),
+    })
+}
+
+/// converts the json value a transport received to a message, reported like `Message::try_from_js`
+pub(crate) fn try_from_json<T: MsgSafe>(value: serde_json::Value) -> Result<T, ConversionError> {
+    serde_path_to_error::deserialize(value).map_err(|err| ConversionError::FromJs {
+        type_name: std::any::type_name::<T>(),
+        path: err.path().to_string(),
+        message: err.inner().to_string(),
+    })
+}
@@ -0,0 +1,114 @@
+use crate::tauri_wasm::{ConversionError, MsgSafe, try_from_json, try_to_json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The content type `HttpTransport` and `HttpRouter` send binary payloads with.
+pub const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The header `TauriTransport` sends the request id of a binary command in,
+/// json commands send it as `requestId` next to `args`.
+pub const REQUEST_ID_HEADER: &str = "slvr-request-id";
+
+/// What the generated tauri commands return, the response carries a json or a binary payload.
+#[cfg(feature = "tauri_wasm_backend")]
+pub type InvokeResult = Result<tauri::ipc::Response, Value>;
+
+/// A command or a response on its way between the frontend and the backend.
+///
+/// Messages are sent as json unless their type sets `MsgSafe::BINARY`, then they are encoded
+/// with postcard and sent as raw bytes: a `Uint8Array` invoke body and a raw tauri response.
+/// That skips the conversion to and from js objects, where a `Vec<u8>` becomes an array holding a
+/// number for every byte. It is not zero-copy, the frontend still copies the bytes between the js
+/// heap and wasm memory.
+///
+/// Postcard is not self-describing, so binary messages cannot use `untagged`, `flatten` or
+/// internally tagged enums, nor hold a `serde_json::Value`. Rejections are always json.
+/// Tauri does not support raw invoke bodies on android.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Payload {
+    Json(Value),
+    Binary(Vec<u8>),
+}
+
+impl MsgSafe for Payload {}
+
+impl Payload {
+    /// Encodes `message` as binary if its type is `MsgSafe::BINARY`, as json otherwise.
+    pub fn encode<T: MsgSafe>(message: &T) -> Result<Payload, ConversionError> {
+        if T::BINARY {
+            postcard::to_allocvec(message)
+                .map(Payload::Binary)
+                .map_err(|err| ConversionError::ToBinary {
+                    type_name: std::any::type_name::<T>(),
+                    message: err.to_string(),
+                })
+        } else {
+            try_to_json(message).map(Payload::Json)
+        }
+    }
+
+    /// Decodes the payload as `T`, however it was encoded.
+    pub fn decode<T: MsgSafe>(self) -> Result<T, ConversionError> {
+        match self {
+            Payload::Json(value) => try_from_json(value),
+            Payload::Binary(bytes) => from_binary(&bytes),
+        }
+    }
+
+    /// The value of a json payload.
+    pub fn json(&self) -> Option<&Value> {
+        match self {
+            Payload::Json(value) => Some(value),
+            Payload::Binary(_) => None,
+        }
+    }
+
+    /// The tauri response carrying the payload, raw bytes for binary payloads.
+    #[cfg(feature = "tauri_wasm_backend")]
+    pub fn into_response(self) -> tauri::ipc::Response {
+        match self {
+            Payload::Json(value) => tauri::ipc::Response::new(value.to_string()),
+            Payload::Binary(bytes) => tauri::ipc::Response::new(bytes),
+        }
+    }
+}
+
+fn from_binary<T: MsgSafe>(bytes: &[u8]) -> Result<T, ConversionError> {
+    postcard::from_bytes(bytes).map_err(|err| ConversionError::FromBinary {
+        type_name: std::any::type_name::<T>(),
+        message: err.to_string(),
+    })
+}
+
+/// Reads the command and the request id the frontend sent, called by the generated tauri commands.
+///
+/// Json invokes carry them as `args` and `requestId`, binary ones as the body and `REQUEST_ID_HEADER`.
+/// Failures are rejected the way tauri rejects invalid arguments.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn request_args<C: MsgSafe>(
+    command_name: &str,
+    request: &tauri::ipc::Request<'_>,
+) -> Result<(C, Option<u64>), Value> {
+    let invalid_args = |err: &dyn std::fmt::Display| {
+        format!("invalid args `args` for command `{command_name}`: {err}")
+    };
+
+    match request.body() {
+        tauri::ipc::InvokeBody::Json(body) => {
+            let args = body.get("args").ok_or_else(|| {
+                Value::String(format!("command {command_name} missing required key args"))
+            })?;
+            let command = C::deserialize(args).map_err(|err| Value::String(invalid_args(&err)))?;
+            Ok((command, body.get("requestId").and_then(Value::as_u64)))
+        }
+        tauri::ipc::InvokeBody::Raw(bytes) => {
+            let command = from_binary(bytes).map_err(|err| Value::String(invalid_args(&err)))?;
+            let request_id = request
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|request_id| request_id.to_str().ok()?.parse().ok());
+            Ok((command, request_id))
+        }
+    }
+}
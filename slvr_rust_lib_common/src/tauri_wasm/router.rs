@@ -0,0 +1,163 @@
+use crate::tauri_wasm::MsgSafe;
+use crate::tauri_wasm::batch::{
+    BATCH_COMMAND_NAME, BatchRequest, batch_args, run_batch, unknown_command,
+};
+use crate::tauri_wasm::command::{BackendError, TauriCommand, TauriResult};
+use crate::tauri_wasm::context::CommandContext;
+use crate::tauri_wasm::middleware::{CommandFuture, CommandResult, run_command};
+use crate::tauri_wasm::payload::{BINARY_CONTENT_TYPE, Payload};
+use crate::tauri_wasm::schema::{HANDSHAKE_COMMAND_NAME, TauriSchema};
+use axum::Json;
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+
+type BatchHandler = Arc<dyn Fn(Value) -> CommandFuture<'static> + Send + Sync>;
+
+/// Serves `TauriCommand`s over http, the backend half of `transport::HttpTransport`.
+///
+/// Every command is posted as json to `/{COMMAND_NAME}` and handled by the same `handle` as the
+/// tauri command. Responses are sent back with `200 OK`, backend errors as a `BackendError`
+/// with `400 Bad Request`. Binary commands and responses use `payload::BINARY_CONTENT_TYPE`.
+/// Handlers get a `CommandContext` without a window and run through the same `CommandMiddlewares`.
+/// A `Batch` is posted to `/__slvr_batch` and can hold every routed command,
+/// the handshake of `check_schema` is answered once a `schema` is given.
+/// ```ignore
+/// let router = HttpRouter::new(app.handle().clone())
+///     .command::<CheckScreenshot, _, _>()
+///     .into_router();
+/// ```
+pub struct HttpRouter<R: Runtime> {
+    app: AppHandle<R>,
+    router: axum::Router,
+    batch_handlers: HashMap<&'static str, BatchHandler>,
+}
+
+impl<R: Runtime> HttpRouter<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        HttpRouter {
+            app,
+            router: axum::Router::new(),
+            batch_handlers: HashMap::new(),
+        }
+    }
+
+    /// Routes `/{C::COMMAND_NAME}` to `C::handle`.
+    pub fn command<C, Resp, Error>(mut self) -> Self
+    where
+        C: TauriCommand<Resp, Error> + 'static,
+        Resp: TauriResult + 'static,
+        Error: MsgSafe + 'static,
+    {
+        let app = self.app.clone();
+        let handler = move |headers: HeaderMap, body: Bytes| {
+            let context = CommandContext::without_window(app.clone());
+            async move {
+                let command = match request_command::<C>(&headers, &body) {
+                    Ok(command) => command,
+                    Err(rejection) => return command_response(Err(rejection)),
+                };
+                command_response(
+                    run_command(
+                        C::COMMAND_NAME,
+                        command,
+                        context,
+                        |command, context| async move {
+                            command.handle(context).await.map_err(BackendError::new)
+                        },
+                    )
+                    .await,
+                )
+            }
+        };
+
+        self.router = self.router.route(
+            &format!("/{}", C::COMMAND_NAME),
+            axum::routing::post(handler),
+        );
+
+        let app = self.app.clone();
+        let batch_handler: BatchHandler = Arc::new(move |args| {
+            let context = CommandContext::without_window(app.clone());
+            Box::pin(async move {
+                let command = batch_args::<C>(args)?;
+                run_command(
+                    C::COMMAND_NAME,
+                    command,
+                    context,
+                    |command, context| async move {
+                        command.handle(context).await.map_err(BackendError::new)
+                    },
+                )
+                .await
+            })
+        });
+        self.batch_handlers.insert(C::COMMAND_NAME, batch_handler);
+        self
+    }
+
+    /// Answers the handshake of `check_schema` on `/__slvr_handshake` with `schema`, ie: `TAURI_SCHEMA`.
+    pub fn schema(mut self, schema: &TauriSchema) -> Self {
+        let summary = schema.summary();
+        self.router = self.router.route(
+            &format!("/{HANDSHAKE_COMMAND_NAME}"),
+            axum::routing::post(move || async move { Json(summary) }),
+        );
+        self
+    }
+
+    pub fn into_router(self) -> axum::Router {
+        let handlers = Arc::new(self.batch_handlers);
+        let batch = move |Json(request): Json<BatchRequest>| async move {
+            let results = run_batch(request, |command_name, args| {
+                let handler = handlers.get(command_name.as_str()).cloned();
+                async move {
+                    match handler {
+                        Some(handler) => handler(args).await,
+                        None => Err(unknown_command(&command_name)),
+                    }
+                }
+            })
+            .await;
+            Json(results)
+        };
+
+        self.router.route(
+            &format!("/{BATCH_COMMAND_NAME}"),
+            axum::routing::post(batch),
+        )
+    }
+}
+
+/// reads the command from a json or a binary body, depending on its content type
+fn request_command<C: MsgSafe>(headers: &HeaderMap, body: &Bytes) -> Result<C, Value> {
+    let binary = headers
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type == BINARY_CONTENT_TYPE);
+    let payload = if binary {
+        Payload::Binary(body.to_vec())
+    } else {
+        Payload::Json(serde_json::from_slice(body).map_err(|err| Value::String(err.to_string()))?)
+    };
+
+    payload
+        .decode()
+        .map_err(|err| Value::String(err.to_string()))
+}
+
+fn command_response(result: CommandResult) -> Response {
+    match result {
+        Ok(Payload::Json(response)) => (StatusCode::OK, Json(response)).into_response(),
+        Ok(Payload::Binary(response)) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, BINARY_CONTENT_TYPE)],
+            response,
+        )
+            .into_response(),
+        Err(rejection) => (StatusCode::BAD_REQUEST, Json(rejection)).into_response(),
+    }
+}
@@ -0,0 +1,183 @@
+use crate::tauri_wasm::MsgSafe;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::command::{CommandError, NoError};
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::payload::Payload;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::transport::current_transport;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::error::Error;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::fmt::{Display, Formatter};
+
+/// The command the frontend sends to get the schema of the backend, handled by `tauri_commands!()`.
+pub const HANDSHAKE_COMMAND_NAME: &str = "__slvr_handshake";
+
+/// The fingerprints of the messages of a build, generated by `slvr_rust_lib_build` as `TAURI_SCHEMA`.
+///
+/// The fingerprints are hex strings, so they survive the trip through js numbers.
+#[derive(Debug, Clone, Copy)]
+pub struct TauriSchema {
+    /// a hash over every message and command, equal fingerprints mean compatible builds
+    pub fingerprint: &'static str,
+    pub commands: &'static [CommandFingerprint],
+}
+
+/// The fingerprint of a command, a hash over its signature and every message it sends or receives.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandFingerprint {
+    pub command_name: &'static str,
+    pub fingerprint: &'static str,
+}
+
+/// A `TauriSchema` as the backend sends it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaSummary {
+    pub fingerprint: String,
+    /// the fingerprint of every command, by command name
+    pub commands: BTreeMap<String, String>,
+}
+
+impl MsgSafe for SchemaSummary {}
+
+impl TauriSchema {
+    pub fn summary(&self) -> SchemaSummary {
+        SchemaSummary {
+            fingerprint: self.fingerprint.to_string(),
+            commands: self
+                .commands
+                .iter()
+                .map(|command| {
+                    (
+                        command.command_name.to_string(),
+                        command.fingerprint.to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Compares this schema with the one of the other build, `None` if they are compatible.
+    pub fn compare(&self, other: &SchemaSummary) -> Option<SchemaMismatch> {
+        if self.fingerprint == other.fingerprint {
+            return None;
+        }
+
+        let own = self.summary().commands;
+        let mut mismatch = SchemaMismatch::default();
+        for (command_name, fingerprint) in &own {
+            match other.commands.get(command_name) {
+                Some(other_fingerprint) if other_fingerprint == fingerprint => {}
+                Some(_) => mismatch.changed.push(command_name.clone()),
+                None => mismatch.missing_in_other.push(command_name.clone()),
+            }
+        }
+        mismatch.missing_here.extend(
+            other
+                .commands
+                .keys()
+                .filter(|command_name| !own.contains_key(*command_name))
+                .cloned(),
+        );
+
+        Some(mismatch)
+    }
+}
+
+/// How two builds with different schemas differ, see `TauriSchema::compare`.
+///
+/// All lists can be empty when only messages no command uses differ, ie: events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// commands both builds know whose messages differ
+    pub changed: Vec<String>,
+    /// commands only the comparing build knows
+    pub missing_in_other: Vec<String>,
+    /// commands only the other build knows
+    pub missing_here: Vec<String>,
+}
+
+/// Why `check_schema` failed.
+#[cfg(feature = "tauri_wasm_frontend")]
+#[derive(Debug)]
+pub enum HandshakeError {
+    /// The backend was built from other messages.
+    Mismatch(SchemaMismatch),
+    /// The handshake could not be sent, ie: the backend is older than the handshake.
+    Command(CommandError<NoError>),
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl Display for HandshakeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandshakeError::Mismatch(mismatch) => {
+                write!(
+                    f,
+                    "The frontend and the backend were built from other messages"
+                )?;
+                for (label, commands) in [
+                    ("changed", &mismatch.changed),
+                    ("missing in the backend", &mismatch.missing_in_other),
+                    ("missing in the frontend", &mismatch.missing_here),
+                ] {
+                    if !commands.is_empty() {
+                        write!(f, ", {label}: {}", commands.join(", "))?;
+                    }
+                }
+                Ok(())
+            }
+            HandshakeError::Command(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl Error for HandshakeError {}
+
+/// Checks that the backend was built from the same messages as the frontend, call it at startup
+/// with the `TAURI_SCHEMA` from `OUT_DIR/tauri_schema.rs`:
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/tauri_schema.rs"));
+///
+/// if let Err(err) = check_schema(&TAURI_SCHEMA).await {
+///     log::error!("{err}");
+/// }
+/// ```
+#[cfg(feature = "tauri_wasm_frontend")]
+pub async fn check_schema(schema: &TauriSchema) -> Result<(), HandshakeError> {
+    let backend = match current_transport()
+        .invoke(
+            HANDSHAKE_COMMAND_NAME,
+            Payload::Json(serde_json::Value::Null),
+        )
+        .await
+    {
+        Ok(payload) => payload
+            .decode::<SchemaSummary>()
+            .map_err(|err| HandshakeError::Command(err.into()))?,
+        Err(rejection) => {
+            return Err(HandshakeError::Command(CommandError::from_rejection(
+                rejection,
+            )));
+        }
+    };
+
+    match schema.compare(&backend) {
+        Some(mismatch) => Err(HandshakeError::Mismatch(mismatch)),
+        None => Ok(()),
+    }
+}
+
+/// Handles `HANDSHAKE_COMMAND_NAME`, answering with the schema of the backend.
+#[doc(hidden)]
+#[cfg(feature = "tauri_wasm_backend")]
+pub fn handshake_invoke<R: tauri::Runtime>(
+    invoke: tauri::ipc::Invoke<R>,
+    schema: &TauriSchema,
+) -> bool {
+    invoke.resolver.resolve(schema.summary());
+    true
+}
@@ -0,0 +1,225 @@
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::Message;
+use crate::tauri_wasm::MsgSafe;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::cancellation::next_request_id;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::command::CommandError;
+use crate::tauri_wasm::command::{NoError, TauriResult};
+#[cfg(feature = "tauri_wasm_backend")]
+use crate::tauri_wasm::context::CommandContext;
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::transport::{cancel_request, js_to_json};
+#[cfg(feature = "tauri_wasm_frontend")]
+use crate::tauri_wasm::try_to_js_json;
+#[cfg(feature = "tauri_wasm_frontend")]
+use futures::Stream;
+#[cfg(feature = "tauri_wasm_frontend")]
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+#[cfg(feature = "tauri_wasm_frontend")]
+use futures::stream::FusedStream;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::pin::Pin;
+#[cfg(feature = "tauri_wasm_frontend")]
+use std::task::{Context, Poll};
+#[cfg(feature = "tauri_wasm_frontend")]
+use wasm_bindgen::prelude::{Closure, JsCast, JsValue, wasm_bindgen};
+
+#[cfg(feature = "tauri_wasm_frontend")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = Channel)]
+    type TauriChannel;
+
+    #[wasm_bindgen(constructor, js_namespace = ["window", "__TAURI__", "core"], js_class = Channel)]
+    fn new() -> TauriChannel;
+
+    #[wasm_bindgen(method, setter, js_class = Channel, js_name = onmessage)]
+    fn set_onmessage(this: &TauriChannel, handler: &js_sys::Function);
+
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    async fn invoke_args(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+}
+
+/// The messages a streaming command sends over its channel.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum StreamMessage<Item> {
+    Item(Item),
+    /// The backend handled the command successfully, nothing else will be sent. A failed command
+    /// sends no `End`, its invoke is rejected instead.
+    End,
+}
+
+impl<Item: MsgSafe> MsgSafe for StreamMessage<Item> {}
+
+/// A command that answers with many `Item`s instead of a single response,
+/// ie: a directory scan or a log tail.
+pub trait TauriStreamCommand<Item: TauriResult, Error: MsgSafe = NoError>: MsgSafe {
+    const COMMAND_NAME: &'static str;
+
+    /// Sends the command to the backend and returns a stream of the items it sends back.
+    ///
+    /// The stream ends when the backend finishes handling the command. If the command fails,
+    /// the last item of the stream is the error, see `TauriCommand::send` for the kinds of errors.
+    /// Dropping the stream before it ends cancels the command, see `CommandContext::cancellation`.
+    /// Streaming commands always go through tauri's ipc, whatever transport is set.
+    #[cfg(feature = "tauri_wasm_frontend")]
+    fn send(self) -> CommandStream<Item, Error>
+    where
+        Self: 'static,
+        Item: 'static,
+        Error: 'static,
+    {
+        CommandStream::new(Self::COMMAND_NAME, self)
+    }
+
+    /// Handles the command, every item sent to `sink` is passed on to the frontend.
+    /// See `TauriCommand::handle` for `context`.
+    #[cfg(feature = "tauri_wasm_backend")]
+    fn handle<R: tauri::Runtime>(
+        self,
+        context: CommandContext<R>,
+        sink: StreamSink<Item>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+/// Sends the items of a streaming command to the frontend.
+#[cfg(feature = "tauri_wasm_backend")]
+pub struct StreamSink<Item> {
+    channel: tauri::ipc::Channel<StreamMessage<Item>>,
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+impl<Item: MsgSafe> StreamSink<Item> {
+    pub fn new(channel: tauri::ipc::Channel<StreamMessage<Item>>) -> Self {
+        StreamSink { channel }
+    }
+
+    pub fn send(&self, item: Item) -> tauri::Result<()> {
+        self.channel.send(StreamMessage::Item(item))
+    }
+
+    /// Tells the frontend that no more items will be sent, called by the generated command.
+    pub fn end(self) -> tauri::Result<()> {
+        self.channel.send(StreamMessage::End)
+    }
+}
+
+#[cfg(feature = "tauri_wasm_backend")]
+impl<Item> Clone for StreamSink<Item> {
+    fn clone(&self) -> Self {
+        StreamSink {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+/// The items sent by a streaming command, see `TauriStreamCommand::send`.
+#[cfg(feature = "tauri_wasm_frontend")]
+pub struct CommandStream<Item, Error> {
+    receiver: UnboundedReceiver<Result<Item, CommandError<Error>>>,
+    channel: TauriChannel,
+    request_id: u64,
+    /// the channel calls this until the stream is dropped
+    _handler: Closure<dyn FnMut(JsValue)>,
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<Item: MsgSafe + 'static, Error: MsgSafe + 'static> CommandStream<Item, Error> {
+    fn new<Command: MsgSafe + 'static>(command_name: &'static str, command: Command) -> Self {
+        let (sender, receiver) = unbounded();
+        let channel = TauriChannel::new();
+
+        let handler_sender = sender.clone();
+        let handler =
+            Closure::<dyn FnMut(JsValue)>::new(move |message: JsValue| {
+                match StreamMessage::<Item>::try_from_js(message) {
+                    Ok(StreamMessage::Item(item)) => {
+                        let _ = handler_sender.unbounded_send(Ok(item));
+                    }
+                    Ok(StreamMessage::End) => handler_sender.close_channel(),
+                    Err(err) => {
+                        let _ = handler_sender.unbounded_send(Err(err.into()));
+                    }
+                }
+            });
+        channel.set_onmessage(handler.as_ref().unchecked_ref());
+
+        let stream = CommandStream {
+            receiver,
+            channel,
+            request_id: next_request_id(),
+            _handler: handler,
+        };
+
+        // converted like `TauriTransport` converts commands, tauri reads js `Map`s as empty objects
+        match try_to_js_json(&command) {
+            Ok(args) => invoke_stream(
+                command_name,
+                args,
+                stream.request_id,
+                &stream.channel,
+                sender,
+            ),
+            Err(err) => {
+                let _ = sender.unbounded_send(Err(err.into()));
+                sender.close_channel();
+            }
+        }
+
+        stream
+    }
+}
+
+/// invokes the command in the background, failures end the stream with an error
+#[cfg(feature = "tauri_wasm_frontend")]
+fn invoke_stream<Item: 'static, Error: MsgSafe + 'static>(
+    command_name: &'static str,
+    args: JsValue,
+    request_id: u64,
+    channel: &TauriChannel,
+    sender: UnboundedSender<Result<Item, CommandError<Error>>>,
+) {
+    // tauri commands take their arguments by name, the generated command names them `args`, `channel` and `request_id`
+    let invoke_args_object = js_sys::Object::new();
+    js_sys::Reflect::set(&invoke_args_object, &JsValue::from_str("args"), &args)
+        .expect("setting a property on a plain object cannot fail");
+    js_sys::Reflect::set(&invoke_args_object, &JsValue::from_str("channel"), channel)
+        .expect("setting a property on a plain object cannot fail");
+    js_sys::Reflect::set(
+        &invoke_args_object,
+        &JsValue::from_str("requestId"),
+        &JsValue::from_f64(request_id as f64),
+    )
+    .expect("setting a property on a plain object cannot fail");
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(rejection) = invoke_args(command_name, invoke_args_object.into()).await {
+            let _ = sender.unbounded_send(Err(CommandError::from_rejection(js_to_json(rejection))));
+            sender.close_channel();
+        }
+    });
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<Item, Error> Stream for CommandStream<Item, Error> {
+    type Item = Result<Item, CommandError<Error>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "tauri_wasm_frontend")]
+impl<Item, Error> Drop for CommandStream<Item, Error> {
+    fn drop(&mut self) {
+        // the backend may still be sending, so the channel gets a handler that outlives the stream
+        self.channel
+            .set_onmessage(&js_sys::Function::new_with_args("_message", ""));
+        if !self.receiver.is_terminated() {
+            cancel_request(self.request_id);
+        }
+    }
+}
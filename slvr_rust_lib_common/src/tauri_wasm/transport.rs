@@ -0,0 +1,331 @@
+use crate::tauri_wasm::cancellation::CANCEL_COMMAND_NAME;
+use crate::tauri_wasm::payload::{BINARY_CONTENT_TYPE, Payload, REQUEST_ID_HEADER};
+use crate::tauri_wasm::{ConversionError, MsgSafe, try_to_js_json};
+use futures::future::LocalBoxFuture;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::{JsCast, JsValue, wasm_bindgen};
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    async fn invoke_args(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
+    async fn invoke_raw(cmd: &str, body: JsValue, options: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch(url: &str, init: &JsValue) -> js_sys::Promise;
+
+    type FetchResponse;
+
+    #[wasm_bindgen(method, getter)]
+    fn ok(this: &FetchResponse) -> bool;
+
+    #[wasm_bindgen(method)]
+    fn text(this: &FetchResponse) -> js_sys::Promise;
+
+    #[wasm_bindgen(method, js_name = arrayBuffer)]
+    fn array_buffer(this: &FetchResponse) -> js_sys::Promise;
+
+    #[wasm_bindgen(method, getter)]
+    fn headers(this: &FetchResponse) -> FetchHeaders;
+
+    type FetchHeaders;
+
+    #[wasm_bindgen(method)]
+    fn get(this: &FetchHeaders, name: &str) -> Option<String>;
+}
+
+/// How `TauriCommand::send` reaches the backend.
+///
+/// The transport only moves payloads around, `args` is the encoded command and the result is
+/// either the encoded response or the json rejection, which is a `BackendError` when the backend
+/// handled the command and returned an error.
+pub trait Transport {
+    fn invoke(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>>;
+
+    /// Invokes a command that `cancel` may cancel later, used by `TauriCommand::send_with`.
+    /// Transports that cannot cancel commands on the backend invoke it like any other.
+    fn invoke_cancellable(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+        request_id: u64,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        let _ = request_id;
+        self.invoke(command_name, args)
+    }
+
+    /// Tells the backend the frontend gave up on the command sent with `request_id`.
+    fn cancel(&self, command_name: &'static str, request_id: u64) {
+        let _ = (command_name, request_id);
+    }
+
+    /// Whether json commands are serialized straight to js values and passed to `invoke_js`
+    /// instead of `invoke`, which saves building a `serde_json::Value` first.
+    fn takes_js_args(&self) -> bool {
+        false
+    }
+
+    /// Invokes a json command whose arguments are plain js objects, only called when `takes_js_args`
+    /// is true. `request_id` is set for commands sent with `TauriCommand::send_with`.
+    fn invoke_js(
+        &self,
+        command_name: &'static str,
+        args: JsValue,
+        request_id: Option<u64>,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        let args = Payload::Json(js_to_json(args));
+        match request_id {
+            Some(request_id) => self.invoke_cancellable(command_name, args, request_id),
+            None => self.invoke(command_name, args),
+        }
+    }
+}
+
+thread_local! {
+    static TRANSPORT: RefCell<Rc<dyn Transport>> = RefCell::new(Rc::new(TauriTransport));
+}
+
+/// Replaces the transport used by every following `TauriCommand::send`, the default is `TauriTransport`.
+///
+/// Streaming commands and events always go through tauri.
+pub fn set_transport(transport: impl Transport + 'static) {
+    TRANSPORT.with(|current| *current.borrow_mut() = Rc::new(transport));
+}
+
+pub(crate) fn current_transport() -> Rc<dyn Transport> {
+    TRANSPORT.with(|current| current.borrow().clone())
+}
+
+/// encodes `command` for `transport` and invokes it, through `Transport::invoke_js` when the
+/// transport takes json commands as js values, cancellable when there is a `request_id`
+pub(crate) fn invoke_command<C: MsgSafe>(
+    transport: &dyn Transport,
+    command_name: &'static str,
+    command: &C,
+    request_id: Option<u64>,
+) -> Result<LocalBoxFuture<'static, Result<Payload, Value>>, ConversionError> {
+    if !C::BINARY && transport.takes_js_args() {
+        let args = try_to_js_json(command)?;
+        return Ok(transport.invoke_js(command_name, args, request_id));
+    }
+
+    let args = Payload::encode(command)?;
+    Ok(match request_id {
+        Some(request_id) => transport.invoke_cancellable(command_name, args, request_id),
+        None => transport.invoke(command_name, args),
+    })
+}
+
+/// Invokes commands through `window.__TAURI__.core.invoke`, only works inside the tauri webview.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TauriTransport;
+
+impl Transport for TauriTransport {
+    fn invoke(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        Box::pin(tauri_invoke(command_name, args, None))
+    }
+
+    fn invoke_cancellable(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+        request_id: u64,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        Box::pin(tauri_invoke(command_name, args, Some(request_id)))
+    }
+
+    fn cancel(&self, _command_name: &'static str, request_id: u64) {
+        cancel_request(request_id);
+    }
+
+    fn takes_js_args(&self) -> bool {
+        true
+    }
+
+    fn invoke_js(
+        &self,
+        command_name: &'static str,
+        args: JsValue,
+        request_id: Option<u64>,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        Box::pin(async move {
+            match invoke_json(command_name, args, request_id).await {
+                Ok(value) => Ok(js_to_payload(value)),
+                Err(rejection) => Err(js_to_json(rejection)),
+            }
+        })
+    }
+}
+
+/// invokes with the arguments the generated tauri commands read, see `payload::request_args`
+async fn tauri_invoke(
+    command_name: &'static str,
+    args: Payload,
+    request_id: Option<u64>,
+) -> Result<Payload, Value> {
+    let response = match args {
+        Payload::Json(args) => invoke_json(command_name, json_to_js(&args), request_id).await,
+        Payload::Binary(bytes) => {
+            let headers = js_sys::Object::new();
+            if let Some(request_id) = request_id {
+                set_property(
+                    &headers,
+                    REQUEST_ID_HEADER,
+                    &JsValue::from_str(&request_id.to_string()),
+                );
+            }
+            let options = js_sys::Object::new();
+            set_property(&options, "headers", &headers);
+
+            let body = js_sys::Uint8Array::from(bytes.as_slice());
+            invoke_raw(command_name, body.into(), options.into()).await
+        }
+    };
+
+    match response {
+        Ok(value) => Ok(js_to_payload(value)),
+        Err(rejection) => Err(js_to_json(rejection)),
+    }
+}
+
+/// tauri commands take their arguments by name, the generated command names its argument `args`
+async fn invoke_json(
+    command_name: &'static str,
+    args: JsValue,
+    request_id: Option<u64>,
+) -> Result<JsValue, JsValue> {
+    let invoke = js_sys::Object::new();
+    set_property(&invoke, "args", &args);
+    if let Some(request_id) = request_id {
+        // a number like serde_json writes it, `JsValue::from(u64)` would be a `BigInt`
+        set_property(&invoke, "requestId", &JsValue::from_f64(request_id as f64));
+    }
+    invoke_args(command_name, invoke.into()).await
+}
+
+/// sends `CANCEL_COMMAND_NAME` in the background, there is nothing to do if it fails
+pub(crate) fn cancel_request(request_id: u64) {
+    let args = json_to_js(&serde_json::json!({ "requestId": request_id }));
+    wasm_bindgen_futures::spawn_local(async move {
+        let _ = invoke_args(CANCEL_COMMAND_NAME, args).await;
+    });
+}
+
+/// Posts commands as json to `{base_url}/{COMMAND_NAME}`, ie: a dev server built from `HttpRouter`.
+/// Binary commands and responses are sent with `payload::BINARY_CONTENT_TYPE`.
+///
+/// Lets the frontend run in a normal browser with `trunk serve`, proxy `base_url` through trunk
+/// so the requests stay on the same origin.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    base_url: String,
+}
+
+impl HttpTransport {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        HttpTransport {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn invoke(
+        &self,
+        command_name: &'static str,
+        args: Payload,
+    ) -> LocalBoxFuture<'static, Result<Payload, Value>> {
+        let url = format!("{}/{command_name}", self.base_url);
+
+        Box::pin(async move {
+            let (content_type, body) = match args {
+                Payload::Json(args) => ("application/json", JsValue::from_str(&args.to_string())),
+                Payload::Binary(bytes) => (
+                    BINARY_CONTENT_TYPE,
+                    js_sys::Uint8Array::from(bytes.as_slice()).into(),
+                ),
+            };
+            let headers = js_sys::Object::new();
+            set_property(&headers, "Content-Type", &JsValue::from_str(content_type));
+            let init = js_sys::Object::new();
+            set_property(&init, "method", &JsValue::from_str("POST"));
+            set_property(&init, "headers", &headers);
+            set_property(&init, "body", &body);
+
+            let response: FetchResponse = JsFuture::from(fetch(&url, &init))
+                .await
+                .map_err(js_to_json)?
+                .into();
+
+            // rejections are always json
+            let binary = response
+                .headers()
+                .get("Content-Type")
+                .is_some_and(|content_type| content_type == BINARY_CONTENT_TYPE);
+            if response.ok() && binary {
+                let buffer = JsFuture::from(response.array_buffer())
+                    .await
+                    .map_err(js_to_json)?;
+                return Ok(js_to_payload(buffer));
+            }
+
+            let text = JsFuture::from(response.text())
+                .await
+                .map_err(js_to_json)?
+                .as_string()
+                .unwrap_or_default();
+
+            // rejections that are not json, ie: the router could not read the command, are passed on as text
+            let value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            if response.ok() {
+                Ok(Payload::Json(value))
+            } else {
+                Err(value)
+            }
+        })
+    }
+}
+
+fn set_property(object: &js_sys::Object, key: &str, value: &JsValue) {
+    js_sys::Reflect::set(object, &JsValue::from_str(key), value)
+        .expect("setting a property on a plain object cannot fail");
+}
+
+/// converts with plain objects instead of `Map`s, which is what tauri and `JSON.stringify` expect
+pub(crate) fn json_to_js(value: &Value) -> JsValue {
+    serde::Serialize::serialize(value, &serde_wasm_bindgen::Serializer::json_compatible())
+        .expect("json values can always be converted to js")
+}
+
+/// raw responses arrive as an `ArrayBuffer`, which is copied into wasm memory once
+fn js_to_payload(value: JsValue) -> Payload {
+    match value.dyn_ref::<js_sys::ArrayBuffer>() {
+        Some(buffer) => Payload::Binary(js_sys::Uint8Array::new(buffer).to_vec()),
+        None => Payload::Json(js_to_json(value)),
+    }
+}
+
+/// js errors become their message, values json cannot hold end up as their debug string
+pub(crate) fn js_to_json(value: JsValue) -> Value {
+    if let Some(text) = value.as_string() {
+        return Value::String(text);
+    }
+    if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+        return Value::String(String::from(error.message()));
+    }
+    serde_wasm_bindgen::from_value(value.clone())
+        .unwrap_or_else(|_| Value::String(format!("{value:?}")))
+}
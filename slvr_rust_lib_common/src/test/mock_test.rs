@@ -0,0 +1,287 @@
+use crate::tauri_wasm::MsgSafe;
+use crate::tauri_wasm::batch::Batch;
+use crate::tauri_wasm::cancellation::{AbortHandle, SendOptions};
+use crate::tauri_wasm::command::{CommandError, TauriCommand, TauriResult};
+use crate::tauri_wasm::mock::MockBackend;
+use crate::tauri_wasm::payload::Payload;
+use crate::tauri_wasm::schema::{
+    CommandFingerprint, HandshakeError, SchemaMismatch, TauriSchema, check_schema,
+};
+use futures::executor::block_on;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ReadFile {
+    path: String,
+}
+
+impl MsgSafe for ReadFile {}
+
+impl TauriCommand<FileContents, FileError> for ReadFile {
+    const COMMAND_NAME: &'static str = "read_file";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FileContents {
+    text: String,
+}
+
+impl MsgSafe for FileContents {}
+impl TauriResult for FileContents {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum FileError {
+    NotFound,
+}
+
+impl MsgSafe for FileError {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ListFiles;
+
+impl MsgSafe for ListFiles {}
+
+impl TauriCommand<Vec<(String, Option<u64>)>> for ListFiles {
+    const COMMAND_NAME: &'static str = "list_files";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct UploadImage {
+    bytes: Vec<u8>,
+}
+
+impl MsgSafe for UploadImage {
+    const BINARY: bool = true;
+}
+
+impl TauriCommand<Thumbnail> for UploadImage {
+    const COMMAND_NAME: &'static str = "upload_image";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Thumbnail {
+    bytes: Vec<u8>,
+}
+
+impl MsgSafe for Thumbnail {
+    const BINARY: bool = true;
+}
+
+impl TauriResult for Thumbnail {}
+
+fn read_file(path: &str) -> ReadFile {
+    ReadFile {
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn canned_responses() {
+    let backend = MockBackend::install();
+    backend.respond::<ReadFile, _, _>(FileContents {
+        text: "hello".to_string(),
+    });
+
+    let response = block_on(read_file("a.txt").send()).unwrap();
+
+    assert_eq!(response.text, "hello");
+    assert_eq!(backend.call_count(ReadFile::COMMAND_NAME), 1);
+}
+
+#[test]
+fn handlers_and_recorded_calls() {
+    let backend = MockBackend::install();
+    backend.handle::<ReadFile, _, _>(|command| {
+        if command.path.ends_with(".txt") {
+            Ok(FileContents { text: command.path })
+        } else {
+            Err(FileError::NotFound)
+        }
+    });
+
+    assert_eq!(block_on(read_file("a.txt").send()).unwrap().text, "a.txt");
+    assert!(matches!(
+        block_on(read_file("a.png").send()),
+        Err(CommandError::Backend(FileError::NotFound))
+    ));
+    assert_eq!(
+        backend.calls::<ReadFile, _, _>(),
+        [read_file("a.txt"), read_file("a.png")]
+    );
+}
+
+#[test]
+fn unknown_commands_are_rejected() {
+    let backend = MockBackend::install();
+
+    let response = block_on(read_file("a.txt").send());
+
+    assert!(
+        matches!(response, Err(CommandError::Invoke(message)) if message.contains("read_file"))
+    );
+    assert_eq!(backend.all_calls()[0].args.json().unwrap()["path"], "a.txt");
+}
+
+#[test]
+fn std_responses() {
+    let backend = MockBackend::install();
+    backend.respond::<ListFiles, _, _>(vec![
+        ("a.txt".to_string(), Some(3)),
+        ("b".to_string(), None),
+    ]);
+
+    let response = block_on(ListFiles.send()).unwrap();
+
+    assert_eq!(
+        response,
+        [("a.txt".to_string(), Some(3)), ("b".to_string(), None)]
+    );
+}
+
+#[test]
+fn aborted_commands_are_cancelled() {
+    let backend = MockBackend::install();
+    backend.pending::<ReadFile, _, _>();
+    let abort_handle = AbortHandle::new();
+
+    let (response, _) = block_on(futures::future::join(
+        read_file("a.txt").send_with(SendOptions::new().abort_handle(&abort_handle)),
+        async { abort_handle.abort() },
+    ));
+
+    assert!(matches!(response, Err(CommandError::Cancelled)));
+    assert_eq!(backend.call_count(ReadFile::COMMAND_NAME), 1);
+    assert_eq!(backend.cancel_count(ReadFile::COMMAND_NAME), 1);
+}
+
+#[test]
+fn already_aborted_commands_are_not_sent() {
+    let backend = MockBackend::install();
+    backend.respond::<ReadFile, _, _>(FileContents {
+        text: "hello".to_string(),
+    });
+    let abort_handle = AbortHandle::new();
+    abort_handle.abort();
+
+    let response =
+        block_on(read_file("a.txt").send_with(SendOptions::new().abort_handle(&abort_handle)));
+
+    assert!(matches!(response, Err(CommandError::Cancelled)));
+    assert_eq!(backend.call_count(ReadFile::COMMAND_NAME), 0);
+}
+
+#[test]
+fn batched_commands() {
+    let backend = MockBackend::install();
+    backend.handle::<ReadFile, _, _>(|command| {
+        if command.path.ends_with(".txt") {
+            Ok(FileContents { text: command.path })
+        } else {
+            Err(FileError::NotFound)
+        }
+    });
+    backend.respond::<ListFiles, _, _>(vec![("a.txt".to_string(), None)]);
+
+    let mut batch = Batch::new();
+    let text = batch.add(read_file("a.txt"));
+    let image = batch.add(read_file("a.png"));
+    let files = batch.add(ListFiles);
+    let results = block_on(batch.send()).unwrap();
+
+    assert_eq!(results.get(text).unwrap().text, "a.txt");
+    assert!(matches!(
+        results.get(image),
+        Err(CommandError::Backend(FileError::NotFound))
+    ));
+    assert_eq!(results.get(files).unwrap(), [("a.txt".to_string(), None)]);
+    assert_eq!(
+        backend.calls::<ReadFile, _, _>(),
+        [read_file("a.txt"), read_file("a.png")]
+    );
+}
+
+#[test]
+fn unknown_batched_commands_are_rejected() {
+    let _backend = MockBackend::install();
+
+    let mut batch = Batch::new().in_order();
+    let file = batch.add(read_file("a.txt"));
+    let results = block_on(batch.send()).unwrap();
+
+    assert!(
+        matches!(results.get(file), Err(CommandError::Invoke(message)) if message.contains("read_file"))
+    );
+}
+
+#[test]
+fn binary_commands() {
+    let backend = MockBackend::install();
+    backend.handle::<UploadImage, _, _>(|command| {
+        Ok(Thumbnail {
+            bytes: command.bytes[..2].to_vec(),
+        })
+    });
+    let image = UploadImage {
+        bytes: vec![1, 2, 3, 4],
+    };
+
+    let response = block_on(image.clone().send()).unwrap();
+
+    assert_eq!(response.bytes, [1, 2]);
+    assert!(matches!(backend.all_calls()[0].args, Payload::Binary(_)));
+    assert_eq!(backend.calls::<UploadImage, _, _>(), [image]);
+}
+
+const FRONTEND_SCHEMA: TauriSchema = TauriSchema {
+    fingerprint: "1111111111111111",
+    commands: &[
+        CommandFingerprint {
+            command_name: "list_files",
+            fingerprint: "aaaaaaaaaaaaaaaa",
+        },
+        CommandFingerprint {
+            command_name: "read_file",
+            fingerprint: "bbbbbbbbbbbbbbbb",
+        },
+    ],
+};
+
+const BACKEND_SCHEMA: TauriSchema = TauriSchema {
+    fingerprint: "2222222222222222",
+    commands: &[
+        CommandFingerprint {
+            command_name: "read_file",
+            fingerprint: "cccccccccccccccc",
+        },
+        CommandFingerprint {
+            command_name: "upload_image",
+            fingerprint: "dddddddddddddddd",
+        },
+    ],
+};
+
+#[test]
+fn schema_handshake() {
+    let backend = MockBackend::install();
+
+    assert!(matches!(
+        block_on(check_schema(&FRONTEND_SCHEMA)),
+        Err(HandshakeError::Command(CommandError::Invoke(_)))
+    ));
+
+    backend.schema(FRONTEND_SCHEMA.summary());
+    assert!(block_on(check_schema(&FRONTEND_SCHEMA)).is_ok());
+
+    backend.schema(BACKEND_SCHEMA.summary());
+    match block_on(check_schema(&FRONTEND_SCHEMA)) {
+        Err(HandshakeError::Mismatch(mismatch)) => assert_eq!(
+            mismatch,
+            SchemaMismatch {
+                changed: vec![String::from("read_file")],
+                missing_in_other: vec![String::from("list_files")],
+                missing_here: vec![String::from("upload_image")],
+            }
+        ),
+        result => panic!("expected a mismatch, got {result:?}"),
+    }
+}
@@ -0,0 +1 @@
+mod mock_test;
@@ -8,26 +8,51 @@ mod tauri_wasm;
 
 #[cfg(feature = "tauri_wasm")]
 #[proc_macro_attribute]
+/**
+* Makes the struct or enum a message that can be sent as a `TauriCommand`.
+* The command name defaults to the struct name in snake_case, use `#[tauri_command(name = "...")]` to override it.
+* `#[tauri_command(binary)]` sends the command encoded with postcard instead of json, for commands carrying large byte buffers.
+* `#[tauri_command(permission = "files", windows = ["main"])]` gives the command a tauri permission and the windows allowed to send it,
+* `slvr_rust_lib_build::tauri_wasm::write_capabilities` writes the permission and capability files from them.
+*/
 pub fn tauri_command(attr: TokenStream, item: TokenStream) -> TokenStream {
     tauri_wasm::command_attribute(attr, item)
 }
 
 #[cfg(feature = "tauri_wasm")]
 #[proc_macro_attribute]
+/**
+* Generates the tauri command for an `impl TauriCommand` or `impl TauriStreamCommand` block.
+* `COMMAND_NAME` can be left out to use the name given by `#[tauri_command]`.
+* Generic commands list the types to register instead, each with its own command name:
+* `#[tauri_command_impl(instances(ListPage<File> = "list_file_page", ListPage<Dir> = "list_dir_page"))]`
+*/
 pub fn tauri_command_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     tauri_wasm::command_impl_attribute(attr, item)
 }
 
 #[cfg(feature = "tauri_wasm")]
 #[proc_macro_attribute]
+/**
+* Makes the struct or enum a message that can be returned by a `TauriCommand`.
+* `#[tauri_response(binary)]` sends the response encoded with postcard instead of json.
+*/
 pub fn tauri_response(attr: TokenStream, item: TokenStream) -> TokenStream {
     tauri_wasm::response_attribute(attr, item)
 }
 
+#[cfg(feature = "tauri_wasm")]
+#[proc_macro_attribute]
+/**
+* Makes the struct a `TauriEvent`.
+* The event name defaults to the struct name in snake_case, use `#[tauri_event(name = "...")]` to override it.
+*/
+pub fn tauri_event(attr: TokenStream, item: TokenStream) -> TokenStream {
+    tauri_wasm::event_attribute(attr, item)
+}
+
 #[cfg(feature = "encapsulation")]
 mod encapsulation;
-#[cfg(feature = "encapsulation")]
-mod to_snake_case;
 
 #[cfg(feature = "encapsulation")]
 #[proc_macro_attribute]
@@ -35,7 +60,6 @@ mod to_snake_case;
 * Encapsulates the provided field on the struct it is called on.
 * You can then access that field through the encapsulation trait.
 */
-pub fn encapsulate(attr: TokenStream,  item: TokenStream) -> TokenStream {
+pub fn encapsulate(attr: TokenStream, item: TokenStream) -> TokenStream {
     encapsulation::encapsulate(attr, item)
 }
-
@@ -1,7 +1,14 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span};
-use quote::{ToTokens, quote};
-use syn::{Expr, ImplItem, ItemImpl, ItemStruct, Lit, Type, parse_macro_input, parse_quote};
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use slvr_rust_lib_macro_support::generics::{instance_substitutions, trait_type_argument};
+use slvr_rust_lib_macro_support::to_snake_case::ToSnakeCase;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{
+    Data, DeriveInput, Expr, ExprLit, Generics, ImplItem, ItemImpl, Lit, LitStr, Token, Type,
+    parse_macro_input, parse_quote,
+};
 
 #[cfg(all(feature = "tauri_wasm_backend", feature = "tauri_wasm_frontend"))]
 compile_error!("You may only use frontend or backend, not both");
@@ -9,77 +16,624 @@ compile_error!("You may only use frontend or backend, not both");
 #[cfg(not(any(feature = "tauri_wasm_backend", feature = "tauri_wasm_frontend")))]
 compile_error!("You must use frontend or backend");
 
-pub(crate) fn command_attribute(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut input = parse_macro_input!(item as ItemStruct);
+pub(crate) fn command_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as DeriveInput);
+    if let Err(err) = check_message_input(&input) {
+        return err.to_compile_error().into();
+    }
+
+    let mut command_name: Option<LitStr> = None;
+    let mut binary = false;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("binary") {
+            binary = true;
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            let name: LitStr = meta.value()?.parse()?;
+            if name.value().is_empty() {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "the command name must not be empty",
+                ));
+            }
+            command_name = Some(name);
+            Ok(())
+        } else if meta.path.is_ident("permission") {
+            // only read by `slvr_rust_lib_build`, checked here so mistakes show up in the editor
+            let permission: LitStr = meta.value()?.parse()?;
+            if !is_permission_identifier(&permission.value()) {
+                return Err(syn::Error::new_spanned(
+                    permission,
+                    "permission identifiers may only hold lowercase ascii letters, digits and `-`, \
+                    and may not start or end with `-`",
+                ));
+            }
+            Ok(())
+        } else if meta.path.is_ident("windows") {
+            let windows: syn::ExprArray = meta.value()?.parse()?;
+            for window in &windows.elems {
+                match window {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(label),
+                        ..
+                    }) if !label.value().is_empty() => {}
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            window,
+                            "expected the label of a window, ie: `windows = [\"main\"]`",
+                        ));
+                    }
+                }
+            }
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported tauri_command argument, expected `name`, `binary`, `permission` or `windows`",
+            ))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
 
     input.attrs.push(parse_quote! {#[derive(Debug, slvr_rust_lib::deps::serde::Serialize, slvr_rust_lib::deps::serde::Deserialize)]});
 
     let name = input.ident.clone();
+    let command_name = command_name
+        .unwrap_or_else(|| LitStr::new(&name.unraw().to_string().to_snake_case(), name.span()));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let msg_safe_where_clause = msg_safe_where_clause(&input.generics);
+    let msg_safe_items = msg_safe_items(binary);
 
     quote! {
         #input
-        impl slvr_rust_lib::tauri_wasm::MsgSafe for #name {}
+        impl #impl_generics slvr_rust_lib::tauri_wasm::MsgSafe for #name #ty_generics #msg_safe_where_clause {
+            #msg_safe_items
+        }
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// the `COMMAND_NAME` `#[tauri_command_impl]` uses when the impl block leaves it out
+            #[doc(hidden)]
+            pub const __TAURI_COMMAND_NAME: &'static str = #command_name;
+        }
     }
     .into()
 }
 
-pub(crate) fn command_impl_attribute(_attr: TokenStream, item: TokenStream) -> TokenStream {
+/// the identifiers tauri accepts for permissions
+fn is_permission_identifier(identifier: &str) -> bool {
+    !identifier.is_empty()
+        && !identifier.starts_with('-')
+        && !identifier.ends_with('-')
+        && identifier
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+pub(crate) fn command_impl_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(item as ItemImpl);
 
+    let mut explicit_response: Option<Type> = None;
+    let mut explicit_error: Option<Type> = None;
+    let mut instances: Vec<CommandInstance> = Vec::new();
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("response") {
+            explicit_response = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("error") {
+            explicit_error = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("instances") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            instances.extend(content.parse_terminated(CommandInstance::parse, Token![,])?);
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported tauri_command_impl argument, expected `response`, `error` or `instances`",
+            ))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+
     let struct_name: Ident = match &*input.self_ty {
-        Type::Path(path) => {
-            if let Some(name) = path.path.get_ident() {
-                name.clone()
-            } else {
-                panic!("called impl macro on type with no identifier?");
-            }
+        Type::Path(path) if path.qself.is_none() && !path.path.segments.is_empty() => {
+            path.path.segments.last().unwrap().ident.clone()
+        }
+        self_ty => {
+            return syn::Error::new_spanned(
+                self_ty,
+                "tauri_command_impl must be used on the impl block of a named struct",
+            )
+            .to_compile_error()
+            .into();
         }
-        _ => panic!("called impl macro on non impl type?"),
     };
 
-    let command_name: Ident = {
-        let mut try_name = None;
-        for item in &input.items {
-            if let ImplItem::Const(impl_const) = item {
-                if &impl_const.ident == "COMMAND_NAME" {
-                    if let Expr::Lit(lit) = impl_const.expr.clone() {
-                        if let Lit::Str(lit_str) = lit.lit {
-                            let value = lit_str.value();
-                            try_name = Some(Ident::new(&value, Span::call_site()));
-                        }
-                    }
-                    break;
-                }
-            }
+    let is_generic = !input.generics.params.is_empty();
+    if is_generic && instances.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "generic commands are registered once for every instance, \
+            list them with `#[tauri_command_impl(instances(Command<Type> = \"name\"))]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let Some(instance) = instances.first().filter(|_| !is_generic) {
+        return syn::Error::new_spanned(
+            &instance.self_ty,
+            "only generic commands have instances, set `COMMAND_NAME` instead",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    match command_name(&input) {
+        Ok(Some(name)) if is_generic => {
+            return syn::Error::new_spanned(
+                name,
+                "generic commands get their COMMAND_NAME from `instances`",
+            )
+            .to_compile_error()
+            .into();
         }
-        if let Some(name) = try_name {
-            name
-        } else {
-            panic!("Could not find command name in impl block, is it a string literal?");
+        Ok(Some(_)) => {}
+        // every instance gets its own name, so the generic impl reads it from `TauriCommandInstance`
+        Ok(None) if is_generic => {
+            let self_ty = &input.self_ty;
+            input.generics.make_where_clause().predicates.push(
+                parse_quote! { #self_ty: slvr_rust_lib::tauri_wasm::command::TauriCommandInstance },
+            );
+            input.items.push(parse_quote! {
+                const COMMAND_NAME: &'static str =
+                    <Self as slvr_rust_lib::tauri_wasm::command::TauriCommandInstance>::COMMAND_NAME;
+            });
+        }
+        // the name given by `#[tauri_command]`, `slvr_rust_lib_build` works it out the same way
+        Ok(None) => input.items.push(parse_quote! {
+            const COMMAND_NAME: &'static str = Self::__TAURI_COMMAND_NAME;
+        }),
+        Err(err) => return err.to_compile_error().into(),
+    }
+
+    let Some((_, trait_path, _)) = &input.trait_ else {
+        return syn::Error::new_spanned(
+            &input.self_ty,
+            "tauri_command_impl must be used on an `impl TauriCommand<Response> for Command` \
+            or `impl TauriStreamCommand<Item> for Command` block",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let response_type: Type = match explicit_response.or_else(|| trait_type_argument(&input, 0)) {
+        Some(response_type) => response_type,
+        None => {
+            return syn::Error::new_spanned(
+                trait_path,
+                "could not work out the response type of this command, \
+                write it as `TauriCommand<Response>` or pass it with `#[tauri_command_impl(response = Response)]`",
+            )
+            .to_compile_error()
+            .into();
         }
     };
 
+    let error_type: Type = explicit_error
+        .or_else(|| trait_type_argument(&input, 1))
+        .unwrap_or_else(|| parse_quote! { slvr_rust_lib::tauri_wasm::command::NoError });
+
+    let is_stream = trait_path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "TauriStreamCommand");
+
+    let mut generated = TokenStream2::new();
+    if instances.is_empty() {
+        generated.extend(command_wrapper(CommandWrapper {
+            struct_name: &struct_name,
+            instance: None,
+            self_ty: &input.self_ty,
+            trait_path,
+            response_type: &response_type,
+            error_type: &error_type,
+            is_stream,
+        }));
+    }
+
+    for (index, instance) in instances.iter().enumerate() {
+        let substitutions =
+            match instance_substitutions(&input.generics, &input.self_ty, &instance.self_ty) {
+                Ok(substitutions) => substitutions,
+                Err(err) => return err.to_compile_error().into(),
+            };
+        let instance_ty = &instance.self_ty;
+        let instance_name = &instance.command_name;
+
+        generated.extend(quote! {
+            impl slvr_rust_lib::tauri_wasm::command::TauriCommandInstance for #instance_ty {
+                const COMMAND_NAME: &'static str = #instance_name;
+            }
+        });
+        generated.extend(command_wrapper(CommandWrapper {
+            struct_name: &struct_name,
+            instance: Some(index),
+            self_ty: instance_ty,
+            trait_path: &substitutions.apply(trait_path.clone()),
+            response_type: &substitutions.apply(response_type.clone()),
+            error_type: &substitutions.apply(error_type.clone()),
+            is_stream,
+        }));
+    }
+
     quote! {
         #input
+        #generated
+    }
+    .into()
+}
+
+/// `Command<Type> = "name"`, one of the instances of a generic command
+struct CommandInstance {
+    self_ty: Type,
+    command_name: LitStr,
+}
+
+impl Parse for CommandInstance {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let self_ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let command_name: LitStr = input.parse()?;
+        if command_name.value().is_empty() {
+            return Err(syn::Error::new_spanned(
+                command_name,
+                "the command name must not be empty",
+            ));
+        }
+
+        Ok(CommandInstance {
+            self_ty,
+            command_name,
+        })
+    }
+}
+
+struct CommandWrapper<'a> {
+    struct_name: &'a Ident,
+    /// the index of the instance for generic commands
+    instance: Option<usize>,
+    self_ty: &'a Type,
+    trait_path: &'a syn::Path,
+    response_type: &'a Type,
+    error_type: &'a Type,
+    is_stream: bool,
+}
+
+/// generates the tauri command that calls `handle`, concrete types only as tauri commands cannot be generic
+fn command_wrapper(wrapper: CommandWrapper) -> TokenStream2 {
+    // the frontend has no `handle`, so only the backend gets a tauri command
+    if !cfg!(feature = "tauri_wasm_backend") {
+        return quote! {};
+    }
+
+    let CommandWrapper {
+        struct_name,
+        instance,
+        self_ty,
+        trait_path,
+        response_type,
+        error_type,
+        is_stream,
+    } = wrapper;
+
+    let command_function = command_function_name(struct_name, instance);
+
+    // both go through the `CommandMiddlewares` managed by the app before reaching `handle`
+    let (params, body, ipc_params, ipc_arguments, respond_body) = if is_stream {
+        // streaming commands send their items over the channel and only return once they are done
+        let params = quote! {
+            args: #self_ty,
+            app: tauri::AppHandle<R>,
+            window: tauri::WebviewWindow<R>,
+            channel: tauri::ipc::Channel<slvr_rust_lib::tauri_wasm::stream::StreamMessage<#response_type>>,
+            request_id: Option<u64>,
+        };
+        let arguments = quote! { args, app, window, channel, request_id };
+        (
+            params.clone(),
+            quote! {
+                let sink = slvr_rust_lib::tauri_wasm::stream::StreamSink::new(channel);
+                let handler_sink = sink.clone();
+                let (context, _in_flight) = slvr_rust_lib::tauri_wasm::cancellation::cancellable(
+                    slvr_rust_lib::tauri_wasm::context::CommandContext::new(app, window),
+                    request_id,
+                );
+                let result = slvr_rust_lib::tauri_wasm::middleware::run_command(
+                    <#self_ty as #trait_path>::COMMAND_NAME,
+                    args,
+                    context,
+                    move |args, context| async move {
+                        <#self_ty as #trait_path>::handle(args, context, handler_sink)
+                            .await
+                            .map_err(slvr_rust_lib::tauri_wasm::command::BackendError::<#error_type>::new)
+                    },
+                )
+                    .await;
+                // a failed command ends the stream with the rejection of its invoke instead, which
+                // would not reach the frontend once `End` closed the stream
+                if result.is_ok() {
+                    // the frontend may already be gone, in which case there is nobody to tell
+                    let _ = sink.end();
+                }
+                result
+            },
+            params,
+            arguments.clone(),
+            quote! {
+                #command_function(#arguments)
+                    .await
+                    .map(slvr_rust_lib::tauri_wasm::payload::Payload::into_response)
+            },
+        )
+    } else {
+        (
+            quote! {
+                args: #self_ty,
+                app: tauri::AppHandle<R>,
+                window: tauri::WebviewWindow<R>,
+                request_id: Option<u64>,
+            },
+            quote! {
+                // `request_id` is sent by `send_with`, which may cancel the command later
+                let (context, _in_flight) = slvr_rust_lib::tauri_wasm::cancellation::cancellable(
+                    slvr_rust_lib::tauri_wasm::context::CommandContext::new(app, window),
+                    request_id,
+                );
+                slvr_rust_lib::tauri_wasm::middleware::run_command(
+                    <#self_ty as #trait_path>::COMMAND_NAME,
+                    args,
+                    context,
+                    |args, context| async move {
+                        <#self_ty as #trait_path>::handle(args, context)
+                            .await
+                            .map_err(slvr_rust_lib::tauri_wasm::command::BackendError::<#error_type>::new)
+                    },
+                )
+                    .await
+            },
+            // the command is read from the request itself, it may be json or binary
+            quote! {
+                request: tauri::ipc::Request<'_>,
+                app: tauri::AppHandle<R>,
+                window: tauri::WebviewWindow<R>,
+            },
+            quote! { request, app, window },
+            quote! {
+                let (args, request_id) = slvr_rust_lib::tauri_wasm::payload::request_args::<#self_ty>(
+                    <#self_ty as #trait_path>::COMMAND_NAME,
+                    &request,
+                )?;
+                #command_function(args, app, window, request_id)
+                    .await
+                    .map(slvr_rust_lib::tauri_wasm::payload::Payload::into_response)
+            },
+        )
+    };
+    let return_type = quote! { slvr_rust_lib::tauri_wasm::middleware::CommandResult };
+    let ipc_return_type = quote! { slvr_rust_lib::tauri_wasm::payload::InvokeResult };
+
+    // the wire name is matched by `tauri_commands!()`, so the function names only have to be unique
+    let ipc_function = format_ident!(
+        "__tauri_ipc_{}",
+        command_function_suffix(struct_name, instance)
+    );
+    let respond_function = command_respond_name(struct_name, instance);
+    let tauri_wrapper_macro = format_ident!("__cmd__{}", ipc_function);
+    let invoke_macro = command_invoke_macro_name(struct_name, instance);
+
+    // `tauri_commands!()` calls `#respond_function` through the wrapper macro tauri generates for `#ipc_function`,
+    // batches call `#command_function` with the command they already read.
+    // `#ipc_function` is private so tauri does not `#[macro_export]` its wrapper macro,
+    // which would clash with commands of the same name in other modules
+    quote! {
+        #[doc = concat!("Handles the tauri command of `", stringify!(#self_ty), "`.")]
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        pub(crate) async fn #command_function<R: tauri::Runtime>(#params) -> #return_type {
+            #body
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        pub(crate) async fn #respond_function<R: tauri::Runtime>(#ipc_params) -> #ipc_return_type {
+            #respond_body
+        }
+
+        #[allow(non_snake_case, dead_code)]
         #[tauri::command]
-        async fn #command_name(args: #struct_name) -> CheckScreenshotResp {
-            args.handle().await
+        async fn #ipc_function<R: tauri::Runtime>(#ipc_params) -> #ipc_return_type {
+            #respond_function(#ipc_arguments).await
+        }
+
+        #[doc(hidden)]
+        pub(crate) use #tauri_wrapper_macro as #invoke_macro;
+    }
+}
+
+/// reads the `COMMAND_NAME` string literal from the impl block, `None` if it was left out
+fn command_name(input: &ItemImpl) -> syn::Result<Option<LitStr>> {
+    let Some(impl_const) = input.items.iter().find_map(|item| match item {
+        ImplItem::Const(impl_const) if impl_const.ident == "COMMAND_NAME" => Some(impl_const),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+
+    match &impl_const.expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) if !lit_str.value().is_empty() => Ok(Some(lit_str.clone())),
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => Err(syn::Error::new_spanned(
+            lit_str,
+            "COMMAND_NAME must not be empty",
+        )),
+        expr => Err(syn::Error::new_spanned(
+            expr,
+            "COMMAND_NAME must be a string literal so the command can be registered",
+        )),
+    }
+}
+
+/// `StructName`, or `StructName_{index}` for the instances of generic commands
+fn command_function_suffix(struct_name: &Ident, instance: Option<usize>) -> String {
+    match instance {
+        Some(index) => format!("{}_{index}", struct_name.unraw()),
+        None => struct_name.unraw().to_string(),
+    }
+}
+
+/// the name of the function that handles the command of `struct_name`,
+/// `slvr_rust_lib_build` generates the same name when it registers the command
+fn command_function_name(struct_name: &Ident, instance: Option<usize>) -> Ident {
+    format_ident!(
+        "__tauri_command_{}",
+        command_function_suffix(struct_name, instance)
+    )
+}
+
+/// the name of the function `tauri_commands!()` invokes for the command of `struct_name`,
+/// `slvr_rust_lib_build` generates the same name when it registers the command
+fn command_respond_name(struct_name: &Ident, instance: Option<usize>) -> Ident {
+    format_ident!(
+        "__tauri_respond_{}",
+        command_function_suffix(struct_name, instance)
+    )
+}
+
+/// the name the tauri wrapper macro of the command of `struct_name` is exported as,
+/// `slvr_rust_lib_build` generates the same name when it registers the command
+fn command_invoke_macro_name(struct_name: &Ident, instance: Option<usize>) -> Ident {
+    format_ident!(
+        "__tauri_invoke_{}",
+        command_function_suffix(struct_name, instance)
+    )
+}
+
+pub(crate) fn response_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as DeriveInput);
+    if let Err(err) = check_message_input(&input) {
+        return err.to_compile_error().into();
+    }
+
+    let mut binary = false;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("binary") {
+            binary = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported tauri_response argument, expected `binary`"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+
+    input.attrs.push(parse_quote! {#[derive(Debug, slvr_rust_lib::deps::serde::Serialize, slvr_rust_lib::deps::serde::Deserialize)]});
+
+    let name = input.ident.clone();
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let msg_safe_where_clause = msg_safe_where_clause(&input.generics);
+    let msg_safe_items = msg_safe_items(binary);
+
+    quote! {
+        #input
+        impl #impl_generics slvr_rust_lib::tauri_wasm::MsgSafe for #name #ty_generics #msg_safe_where_clause {
+            #msg_safe_items
         }
+        impl #impl_generics slvr_rust_lib::tauri_wasm::command::TauriResult for #name #ty_generics #msg_safe_where_clause {}
     }
     .into()
 }
 
-pub(crate) fn response_attribute(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut input = parse_macro_input!(item as ItemStruct);
+pub(crate) fn event_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(item as DeriveInput);
+    if let Err(err) = check_message_input(&input) {
+        return err.to_compile_error().into();
+    }
+
+    let mut event_name: Option<LitStr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            let name: LitStr = meta.value()?.parse()?;
+            if name.value().is_empty() {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "the event name must not be empty",
+                ));
+            }
+            event_name = Some(name);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported tauri_event argument, expected `name`"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
 
     input.attrs.push(parse_quote! {#[derive(Debug, slvr_rust_lib::deps::serde::Serialize, slvr_rust_lib::deps::serde::Deserialize)]});
 
     let name = input.ident.clone();
+    let event_name = event_name
+        .unwrap_or_else(|| LitStr::new(&name.unraw().to_string().to_snake_case(), name.span()));
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let msg_safe_where_clause = msg_safe_where_clause(&input.generics);
 
     quote! {
         #input
-        impl slvr_rust_lib::tauri_wasm::MsgSafe for #name {}
-        impl slvr_rust_lib::tauri_wasm::command::TauriResult for #name {}
+        impl #impl_generics slvr_rust_lib::tauri_wasm::MsgSafe for #name #ty_generics #msg_safe_where_clause {}
+        impl #impl_generics slvr_rust_lib::tauri_wasm::event::TauriEvent for #name #ty_generics #msg_safe_where_clause {
+            const EVENT_NAME: &'static str = #event_name;
+        }
     }
     .into()
 }
+
+/// messages are serialized with serde, which supports structs and enums but not unions
+fn check_message_input(input: &DeriveInput) -> syn::Result<()> {
+    match &input.data {
+        Data::Struct(_) | Data::Enum(_) => Ok(()),
+        Data::Union(data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "messages must be structs or enums",
+        )),
+    }
+}
+
+/// the items of the `MsgSafe` impl, binary messages are sent with postcard instead of json
+fn msg_safe_items(binary: bool) -> TokenStream2 {
+    if binary {
+        quote! { const BINARY: bool = true; }
+    } else {
+        quote! {}
+    }
+}
+
+/// the where clause of the message plus a `MsgSafe` bound on every type parameter,
+/// which covers the bounds the serde derives put on their impls
+fn msg_safe_where_clause(generics: &Generics) -> Option<syn::WhereClause> {
+    let mut generics = generics.clone();
+    let type_params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+
+    if !type_params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for param in type_params {
+            where_clause
+                .predicates
+                .push(parse_quote! { #param: slvr_rust_lib::tauri_wasm::MsgSafe });
+        }
+    }
+
+    generics.where_clause
+}
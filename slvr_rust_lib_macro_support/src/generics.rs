@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use syn::visit_mut::VisitMut;
+use syn::{
+    GenericArgument, GenericParam, Generics, Ident, ItemImpl, PathArguments, Type, TypePath,
+};
+
+/// the type parameters of a generic command impl and the types an instance gives them
+#[derive(Clone, Default)]
+pub struct Substitutions(HashMap<Ident, Type>);
+
+impl Substitutions {
+    /// replaces the type parameters in `target` with the types of the instance
+    pub fn apply<T: SubstituteIn>(&self, mut target: T) -> T {
+        target.substitute(&mut SubstitutionVisitor(&self.0));
+        target
+    }
+}
+
+/// what `Substitutions::apply` can replace type parameters in
+pub trait SubstituteIn {
+    fn substitute(&mut self, visitor: &mut SubstitutionVisitor);
+}
+
+impl SubstituteIn for Type {
+    fn substitute(&mut self, visitor: &mut SubstitutionVisitor) {
+        visitor.visit_type_mut(self);
+    }
+}
+
+impl SubstituteIn for syn::Path {
+    fn substitute(&mut self, visitor: &mut SubstitutionVisitor) {
+        visitor.visit_path_mut(self);
+    }
+}
+
+/// replaces the type parameters it has a type for, see `Substitutions::apply`
+pub struct SubstitutionVisitor<'a>(&'a HashMap<Ident, Type>);
+
+impl VisitMut for SubstitutionVisitor<'_> {
+    fn visit_type_mut(&mut self, type_: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = type_
+            && let Some(substitute) = path.get_ident().and_then(|ident| self.0.get(ident))
+        {
+            *type_ = substitute.clone();
+            return;
+        }
+        syn::visit_mut::visit_type_mut(self, type_);
+    }
+}
+
+/// matches the generic arguments of `self_ty`, ie: `ListPage<T>`, against the ones of `instance`,
+/// ie: `ListPage<File>`, to work out which type every type parameter of the impl stands for
+pub fn instance_substitutions(
+    generics: &Generics,
+    self_ty: &Type,
+    instance: &Type,
+) -> syn::Result<Substitutions> {
+    let last_segment = |type_: &Type| match type_ {
+        Type::Path(TypePath { qself: None, path }) => path.segments.last().cloned(),
+        _ => None,
+    };
+    let type_arguments = |segment: &syn::PathSegment| -> Vec<Type> {
+        match &segment.arguments {
+            PathArguments::AngleBracketed(arguments) => arguments
+                .args
+                .iter()
+                .filter_map(|argument| match argument {
+                    GenericArgument::Type(argument_type) => Some(argument_type.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    let (Some(self_segment), Some(instance_segment)) =
+        (last_segment(self_ty), last_segment(instance))
+    else {
+        return Err(syn::Error::new_spanned(
+            instance,
+            "instances must be written as the command type with concrete type arguments",
+        ));
+    };
+    if self_segment.ident != instance_segment.ident {
+        return Err(syn::Error::new_spanned(
+            instance,
+            format!("expected an instance of `{}`", self_segment.ident),
+        ));
+    }
+
+    let mut substitutions = HashMap::new();
+    for (parameter, argument) in type_arguments(&self_segment)
+        .iter()
+        .zip(type_arguments(&instance_segment))
+    {
+        if let Type::Path(TypePath { qself: None, path }) = parameter
+            && let Some(ident) = path.get_ident()
+            && generics.type_params().any(|param| param.ident == *ident)
+        {
+            substitutions.insert(ident.clone(), argument);
+        }
+    }
+
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(type_param) if !substitutions.contains_key(&type_param.ident) => {
+                return Err(syn::Error::new_spanned(
+                    instance,
+                    format!(
+                        "could not work out `{}` from this instance, \
+                        it must be a type argument of the command type",
+                        type_param.ident
+                    ),
+                ));
+            }
+            GenericParam::Type(_) => {}
+            GenericParam::Lifetime(_) | GenericParam::Const(_) => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "generic commands can only have type parameters",
+                ));
+            }
+        }
+    }
+
+    Ok(Substitutions(substitutions))
+}
+
+/// gets the type argument at `index` from the `TauriCommand<Response, Error>` path of the impl block
+pub fn trait_type_argument(input: &ItemImpl, index: usize) -> Option<Type> {
+    let (_, trait_path, _) = input.trait_.as_ref()?;
+
+    if let PathArguments::AngleBracketed(arguments) = &trait_path.segments.last()?.arguments {
+        arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(argument_type) => Some(argument_type.clone()),
+                _ => None,
+            })
+            .nth(index)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,6 @@
+//! What `slvr_rust_lib_macro` and `slvr_rust_lib_build` both need to read the messages the same way,
+//! the build script has to work out the same command and event names as the macros.
+
+pub mod generics;
+// to snake case does not build on current editions of rust because of feature flag weirdness, temporarally copied the code into a file and patched it
+pub mod to_snake_case;